@@ -44,6 +44,17 @@ macro_rules! important {
     }};
 }
 
+macro_rules! planned {
+    ($($arg:tt)*) => {{
+        styled!(
+            ::anstream::stdout(),
+            ::anstyle::AnsiColor::Magenta.on_default(),
+            "[dry-run] {}",
+            format_args!($($arg)*)
+        )
+    }};
+}
+
 macro_rules! warning {
     ($($arg:tt)*) => {{
         styled!(
@@ -1,8 +1,17 @@
-use crate::config::{ModConfig, ToolConfig};
+use crate::config::{BuildOptions, ModConfig, ToolConfig};
+use crate::cooklog::{CookLog, Severity};
+use crate::utils::native_path;
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use std::path::{Path, PathBuf};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// UAT output log (relative to the mod project)
+const UAT_LOG: &str = "Saved/Logs/cvmodtool-uat.log";
 
 const UAT_ARGS: [&str; 12] = [
     "BuildCookRun",
@@ -25,9 +34,22 @@ pub struct Build {
     /// Mod configuration file to use
     #[arg(short, long, default_value = "cvmod.toml")]
     config: PathBuf,
+
+    /// Don't compile the C++ code of the project before cooking
+    #[arg(long)]
+    skip_compile: bool,
+
+    /// Additional UAT arguments (e.g. build -- -compressed)
+    #[arg(last = true)]
+    args: Vec<String>,
 }
 
 impl Build {
+    /// Command with the default arguments and the given mod config
+    pub fn new(config: PathBuf) -> Self {
+        Self::parse_from([OsString::from("build"), "--config".into(), config.into()])
+    }
+
     /// Mod configuration file relative path
     pub fn config(&self) -> &PathBuf {
         &self.config
@@ -42,28 +64,215 @@ impl Build {
         verbose!("Loading tool config...");
         let config = ToolConfig::load()?;
 
+        if !self.skip_compile && has_code(&modconfig) {
+            info!("Running Unreal Build Tool (UBT)...");
+            run_ubt(&modconfig, &config)?;
+        }
+
         info!("Running Unreal Automation Tool (UAT)...");
-        run_uat(&modconfig, &config.uat())?;
+        run_uat(&modconfig, &config, &self.args)?;
 
         info!("Success!");
         Ok(())
     }
 }
 
-fn run_uat(modconfig: &ModConfig, uat: &Path) -> Result<()> {
-    let mut uat_child = Command::new(uat)
+/// Checks if the project has C++ code (Source folder or modules in the .uproject)
+fn has_code(modconfig: &ModConfig) -> bool {
+    if modconfig.wd().join("Source").is_dir() {
+        return true;
+    }
+
+    std::fs::read_to_string(modconfig.uproject())
+        .ok()
+        .and_then(|content| {
+            json::from_str::<json::Value>(content.trim_start_matches('\u{feff}')).ok()
+        })
+        .and_then(|uproject| uproject.get("Modules")?.as_array().map(|m| !m.is_empty()))
+        .unwrap_or(false)
+}
+
+/// Compiles the editor target of the project, so the cook uses the current C++ code
+fn run_ubt(modconfig: &ModConfig, config: &ToolConfig) -> Result<()> {
+    let ubt = config
+        .ubt()
+        .map_err(|e| anyhow!("{e} (Use --skip-compile if the code is already compiled)"))?;
+    let mut ubt = config.command(&ubt);
+    ubt.arg(format!("{}Editor", modconfig.project()))
+        .args(["Win64", "Development"])
+        .arg(format!(
+            "-project=\"{}\"",
+            config.tool_path(&modconfig.uproject())
+        ))
+        .arg("-WaitMutex");
+
+    if crate::utils::dry_run() {
+        planned!("Run: {}", command_line(&ubt));
+        return Ok(());
+    }
+
+    let mut ubt_child = ubt
         .stdin(Stdio::null())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .args(UAT_ARGS.iter())
-        .arg(format!("-project=\"{}\"", modconfig.uproject().display()))
         .spawn()
-        .map_err(|_| anyhow!("UAT failed to start!"))?;
+        .map_err(|e| anyhow!("UBT failed to start! ({e})"))?;
+
+    let ubt_exitcode = ubt_child.wait()?;
+    if !ubt_exitcode.success() {
+        return Err(anyhow!("UBT failed with exit code {ubt_exitcode}!"));
+    }
+
+    Ok(())
+}
+
+/// Runs UAT, teeing its output to the terminal and the UAT log. Prints the cook summary at the end.
+fn run_uat(modconfig: &ModConfig, config: &ToolConfig, args: &[String]) -> Result<()> {
+    let mut uat = config.command(&config.uat()?);
+    uat.args(uat_args(modconfig.build(), args)).arg(format!(
+        "-project=\"{}\"",
+        config.tool_path(&modconfig.uproject())
+    ));
+
+    if crate::utils::dry_run() {
+        planned!("Run: {}", command_line(&uat));
+        return Ok(());
+    }
+
+    let log_path = modconfig.wd().join(native_path(UAT_LOG));
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let log = Mutex::new(File::create(&log_path)?);
+
+    let mut uat_child = uat
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("UAT failed to start! ({e})"))?;
+
+    let stdout = uat_child.stdout.take().unwrap();
+    let stderr = uat_child.stderr.take().unwrap();
+    std::thread::scope(|s| {
+        s.spawn(|| tee(stderr, std::io::stderr(), &log));
+        tee(stdout, std::io::stdout(), &log);
+    });
 
     let uat_exitcode = uat_child.wait()?;
+
+    let output = std::fs::read(&log_path)?;
+    print_summary(&CookLog::parse(&String::from_utf8_lossy(&output)));
+    info!("UAT log saved to {}", log_path.display());
+
     if !uat_exitcode.success() {
         return Err(anyhow!("UAT failed with exit code {uat_exitcode}!"));
     }
 
     Ok(())
 }
+
+/// Program and arguments of the command for printing
+fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// UAT arguments: the defaults adjusted by the build options then the command line ones
+fn uat_args(options: &BuildOptions, args: &[String]) -> Vec<String> {
+    let mut uat_args: Vec<String> = UAT_ARGS
+        .iter()
+        .filter(|arg| !options.removes(arg))
+        .map(|arg| arg.to_string())
+        .collect();
+
+    if options.iterative() {
+        uat_args.push("-iterate".to_string());
+    }
+    if !options.maps().is_empty() {
+        uat_args.push(format!("-map={}", options.maps().join("+")));
+    }
+
+    uat_args.extend(options.extra_args().iter().cloned());
+    uat_args.extend(args.iter().cloned());
+    uat_args
+}
+
+/// Copies the lines of the reader to the output and the log
+fn tee(reader: impl Read, mut output: impl Write, log: &Mutex<File>) {
+    for line in BufReader::new(reader).split(b'\n') {
+        let Ok(mut line) = line else { break };
+        line.push(b'\n');
+
+        let _ = output.write_all(&line);
+        if let Ok(mut log) = log.lock() {
+            let _ = log.write_all(&line);
+        }
+    }
+}
+
+/// Prints the warnings and errors of the cook grouped by asset
+fn print_summary(log: &CookLog) {
+    if log.messages().is_empty() {
+        return;
+    }
+
+    important!(
+        "Cook summary: {} errors, {} warnings",
+        log.count(Severity::Error),
+        log.count(Severity::Warning)
+    );
+    if let Some(result) = log.result() {
+        verbose!("  {result}");
+    }
+
+    // Messages not about an asset last
+    let groups = log.by_asset();
+    let assets = groups.iter().filter(|(asset, _)| asset.is_some());
+    let other = groups.iter().filter(|(asset, _)| asset.is_none());
+
+    for (asset, messages) in assets.chain(other) {
+        info!("  {}", asset.unwrap_or("Other"));
+        for message in messages {
+            match message.severity {
+                Severity::Error => error!("    {}: {}", message.category, message.text),
+                Severity::Warning => warning!("    {}: {}", message.category, message.text),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uat_args() {
+        assert_eq!(uat_args(&BuildOptions::default(), &[]), UAT_ARGS);
+
+        let options: BuildOptions = toml::from_str(
+            r#"
+            maps = ['/Game/Maps/A', '/Game/Maps/B']
+            iterative = true
+            remove_args = ['-targetplatform']
+            extra_args = ['-targetplatform=Win32']
+            "#,
+        )
+        .unwrap();
+        let args = uat_args(&options, &["-compressed".to_string()]);
+
+        assert!(!args.contains(&"-targetplatform=Win64".to_string()));
+        assert_eq!(
+            args[args.len() - 4..],
+            [
+                "-iterate",
+                "-map=/Game/Maps/A+/Game/Maps/B",
+                "-targetplatform=Win32",
+                "-compressed"
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::config::{ToolConfig, ValueOrigin};
+use crate::{engine, steam};
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+
+/// Manage the tool config (config.toml)
+#[derive(Parser)]
+pub struct Config {
+    #[command(subcommand)]
+    subcmd: ConfigCommand,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Create the tool config without prompting
+    Init {
+        /// UE 4.18 install folder (detected if not specified)
+        #[arg(long)]
+        engine: Option<PathBuf>,
+        /// Code Vein "~mods" folder (detected if not specified)
+        #[arg(long)]
+        moddir: Option<PathBuf>,
+        /// Overwrite the existing tool config
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Print the value of a key
+    Get { key: Key },
+    /// Set the value of a key
+    Set { key: Key, value: PathBuf },
+    /// Print the tool config
+    Show {
+        /// Print the effective values with where they came from
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Print the path of the tool config
+    Path,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Key {
+    Engine,
+    Moddir,
+}
+
+impl Config {
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        match &self.subcmd {
+            ConfigCommand::Init {
+                engine,
+                moddir,
+                force,
+            } => init(engine.as_deref(), moddir.as_deref(), *force),
+            ConfigCommand::Get { key } => {
+                let config = ToolConfig::load()?;
+                let value = match key {
+                    Key::Engine => config.engine(),
+                    Key::Moddir => config.moddir(),
+                };
+                println!("{}", value.display());
+                Ok(())
+            }
+            ConfigCommand::Set { key, value } => {
+                let mut config = ToolConfig::load()?;
+                let origin = match key {
+                    Key::Engine => {
+                        config.set_engine(check_engine(value)?);
+                        config.engine_origin()
+                    }
+                    Key::Moddir => {
+                        config.set_moddir(check_moddir(value)?);
+                        config.moddir_origin()
+                    }
+                };
+
+                if crate::utils::dry_run() {
+                    planned!("Write tool config: {}", ToolConfig::path().display());
+                } else {
+                    config.save()?;
+                    info!("Success! Tool config updated.");
+                }
+
+                if let ValueOrigin::Environment(var) = origin {
+                    warning!("The new value is overridden by the {var} environment variable!");
+                }
+                Ok(())
+            }
+            ConfigCommand::Show { origin: false } => {
+                let config = ToolConfig::load()?;
+                print!("{}", toml::to_string_pretty(&config)?);
+                Ok(())
+            }
+            ConfigCommand::Show { origin: true } => {
+                let config = ToolConfig::load()?;
+                important!("Config file: {}", ToolConfig::path().display());
+                info!("  Found in the {}", ToolConfig::source());
+                important!("engine = {}", config.engine().display());
+                info!("  From {}", config.engine_origin());
+                important!("moddir = {}", config.moddir().display());
+                info!("  From {}", config.moddir_origin());
+                Ok(())
+            }
+            ConfigCommand::Path => {
+                println!("{}", ToolConfig::path().display());
+                verbose!("Found in the {}", ToolConfig::source());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn init(engine: Option<&Path>, moddir: Option<&Path>, force: bool) -> Result<()> {
+    if ToolConfig::check() && !force {
+        return Err(anyhow!(
+            "Tool config already exists at {}! Use --force to overwrite it.",
+            ToolConfig::path().display()
+        ));
+    }
+
+    let engine = match engine {
+        Some(engine) => check_engine(engine)?,
+        None => match engine::find().into_iter().next() {
+            Some(engine) => {
+                info!(
+                    "Detected UE {}: {}",
+                    engine.version(),
+                    engine.path().display()
+                );
+                engine.path().to_owned()
+            }
+            None => return Err(anyhow!("No UE 4.18 install was detected! Use --engine.")),
+        },
+    };
+
+    let moddir = match moddir {
+        Some(moddir) => check_moddir(moddir)?,
+        None => match steam::find_game() {
+            Some(game) => {
+                let moddir = steam::moddir(&game);
+                info!("Detected Code Vein \"~mods\" folder: {}", moddir.display());
+                if crate::utils::dry_run() {
+                    planned!("Create directory: {}", moddir.display());
+                } else {
+                    std::fs::create_dir_all(&moddir)?;
+                }
+                moddir
+            }
+            None => return Err(anyhow!("Code Vein was not detected! Use --moddir.")),
+        },
+    };
+
+    if crate::utils::dry_run() {
+        planned!("Write tool config: {}", ToolConfig::path().display());
+        planned!("  engine = {}", engine.display());
+        planned!("  moddir = {}", moddir.display());
+        return Ok(());
+    }
+
+    ToolConfig::new(engine, moddir).save()?;
+    info!(
+        "Success! Tool config created at {}",
+        ToolConfig::path().display()
+    );
+    Ok(())
+}
+
+fn check_engine(engine: &Path) -> Result<PathBuf> {
+    if let Err(err) = engine::verify(engine) {
+        return Err(anyhow!(
+            "Engine directory is not a valid UE 4.18 install: {err}"
+        ));
+    }
+    Ok(engine.to_owned())
+}
+
+fn check_moddir(moddir: &Path) -> Result<PathBuf> {
+    if !moddir.is_dir() {
+        return Err(anyhow!("Mods directory is not a valid directory"));
+    }
+    Ok(moddir.to_owned())
+}
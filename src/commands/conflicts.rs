@@ -0,0 +1,77 @@
+use crate::config::ToolConfig;
+use crate::mods::conflicts::{self, Conflict};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::path::Path;
+
+/// Find assets overridden by more than one installed mod
+#[derive(Parser)]
+pub struct Conflicts {
+    /// Only report conflicts involving this pak file
+    pak: Option<String>,
+}
+
+impl Conflicts {
+    /// Pak file name filter
+    pub fn pak(&self) -> &Option<String> {
+        &self.pak
+    }
+
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        important!("Checking mod conflicts...");
+
+        verbose!("Loading tool config...");
+        let config = ToolConfig::load()?;
+
+        let paks = crate::mods::find_paks(config.moddir())?;
+        verbose!("Found {} pak files", paks.len());
+
+        // Resolve the filter first so a wrong name isn't reported as having no conflicts
+        let filter = match self.pak() {
+            Some(name) => {
+                let name = crate::mods::pak_name(name);
+                let pak = paks
+                    .iter()
+                    .find(|p| p.file_name().is_some_and(|n| n.eq_ignore_ascii_case(&name)))
+                    .ok_or_else(|| anyhow!("Mod not found: {name}"))?;
+                Some(pak.to_owned())
+            }
+            None => None,
+        };
+
+        let mut conflicts = conflicts::find(&paks);
+        if let Some(pak) = filter {
+            conflicts.retain(|c| c.involves(&pak));
+        }
+
+        report(&conflicts);
+        Ok(())
+    }
+}
+
+/// Prints the conflicts and the pak that wins each of them
+pub fn report(conflicts: &[Conflict]) {
+    if conflicts.is_empty() {
+        info!("No conflicts found.");
+        return;
+    }
+
+    warning!("Found {} conflicting assets:", conflicts.len());
+    for conflict in conflicts {
+        let paks = conflict
+            .paks()
+            .iter()
+            .map(|p| file_name(p))
+            .collect::<Vec<_>>()
+            .join(" < ");
+        warning!("  {}", conflict.asset());
+        info!("    {paks} (loaded from {})", file_name(conflict.winner()));
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
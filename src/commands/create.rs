@@ -1,4 +1,4 @@
-use crate::config::{ModConfig, ToolConfig};
+use crate::config::{Manifest, ModConfig};
 use crate::resources::modules::{self, Module};
 use anyhow::{anyhow, Result};
 use clap::Parser;
@@ -15,6 +15,9 @@ pub struct Create {
     /// Additional modules to install
     #[arg(short, long, num_args(0..))]
     modules: Option<Vec<String>>,
+    /// Don't install the dependencies of the modules automatically
+    #[arg(long)]
+    no_deps: bool,
 }
 
 impl Create {
@@ -33,6 +36,11 @@ impl Create {
         &self.modules
     }
 
+    /// Don't resolve module dependencies
+    pub fn no_deps(&self) -> bool {
+        self.no_deps
+    }
+
     /// Execute command
     pub fn execute(&self) -> Result<()> {
         important!("Creating mod project...");
@@ -57,6 +65,21 @@ impl Create {
             }
         };
 
+        if crate::utils::dry_run() {
+            let project_dir = working_dir.join(self.name());
+            planned!("Create directory: {}", project_dir.display());
+            install_modules(&project_dir, self.name(), &selected_modules)?;
+            let modconfig = create_modconfig(self.name(), &project_dir, &selected_modules)?;
+            planned!("Write mod config: {}", project_dir.join(CFG_FILE).display());
+            planned!("  includes = {:?}", modconfig.includes());
+            planned!("Write manifest for {} modules", selected_modules.len());
+            planned!(
+                "Write build script: {}",
+                project_dir.join(BAT_NAME).display()
+            );
+            return Ok(());
+        }
+
         let project_dir = match create_project_dir(working_dir, self.name()) {
             Ok(ret) => ret,
             Err(err) => return Err(anyhow!("Failed to create project directory: {err}")),
@@ -81,6 +104,10 @@ impl Create {
         let smodules = self.get_specified_modules()?;
         let lmodules = modules::load()?;
 
+        if !self.no_deps() {
+            return modules::resolve(&smodules, &lmodules);
+        }
+
         let mut out = Vec::with_capacity(smodules.len());
 
         for smod in smodules {
@@ -95,12 +122,7 @@ impl Create {
 
     fn get_specified_modules(&self) -> Result<Vec<String>> {
         verbose!("Loading profiles...");
-        let mut profiles = crate::resources::profiles::load()?;
-
-        // Load user defined profiles
-        verbose!("Loading tool config...");
-        let config = ToolConfig::load()?;
-        profiles.extend(config.profiles().to_owned());
+        let mut profiles = crate::resources::profiles::load_merged()?;
 
         // Use .remove to take ownership
         if let Some(mut selected) = profiles.remove(self.profile()) {
@@ -170,14 +192,23 @@ fn install_modules<P: AsRef<Path>>(
     Ok(())
 }
 
+const CFG_FILE: &str = "cvmod.toml";
+const BAT_NAME: &str = "build-and-install.bat";
+
 fn create_extra<P: AsRef<Path>>(
     project_dir: P,
     project_name: &str,
     modules: &[Module],
 ) -> Result<()> {
-    const CFG_FILE: &str = "cvmod.toml";
     let modconfig = create_modconfig(project_name, &project_dir, modules)?;
     modconfig.save(project_dir.as_ref().join(CFG_FILE))?;
+
+    let mut manifest = Manifest::load(&project_dir)?;
+    for module in modules {
+        manifest.record(module, project_name)?;
+    }
+    manifest.save()?;
+
     create_bat(project_dir, CFG_FILE)?;
     Ok(())
 }
@@ -198,11 +229,11 @@ fn create_modconfig<P: AsRef<Path>>(
     pakincludes.dedup();
 
     modconfig.set_includes(pakincludes);
+    modconfig.set_modules(modules.iter().map(|m| m.name().to_string()).collect());
     Ok(modconfig)
 }
 
 fn create_bat<P: AsRef<Path>>(project_dir: P, cfg: &str) -> Result<()> {
-    const BAT_NAME: &str = "build-and-install.bat";
     let bat_target_path = project_dir.as_ref().join(BAT_NAME);
     let bat_ref_path = crate::resources::dir().join(BAT_NAME);
 
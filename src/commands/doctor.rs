@@ -0,0 +1,369 @@
+use crate::config::{ModConfig, ToolConfig};
+use crate::engine;
+use crate::pattern::PathPattern;
+use crate::resources::{self, modules, profiles};
+use crate::utils::native_path;
+use anstyle::AnsiColor;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Check the tool config, the resources and the mod project for problems
+#[derive(Parser)]
+pub struct Doctor {
+    /// Mod configuration file to check (skipped if it doesn't exist)
+    #[arg(short, long, default_value = "cvmod.toml")]
+    config: PathBuf,
+
+    /// Treat warnings as failures
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Default)]
+struct Report {
+    passed: usize,
+    warnings: usize,
+    failures: usize,
+}
+
+impl Report {
+    fn pass(&mut self, msg: impl AsRef<str>) {
+        self.passed += 1;
+        styled!(
+            ::anstream::stdout(),
+            AnsiColor::Green.on_default(),
+            "  [PASS] {}",
+            msg.as_ref()
+        );
+    }
+
+    fn warn(&mut self, msg: impl AsRef<str>) {
+        self.warnings += 1;
+        styled!(
+            ::anstream::stdout(),
+            AnsiColor::Yellow.on_default(),
+            "  [WARN] {}",
+            msg.as_ref()
+        );
+    }
+
+    fn fail(&mut self, msg: impl AsRef<str>) {
+        self.failures += 1;
+        styled!(
+            ::anstream::stdout(),
+            AnsiColor::Red.on_default(),
+            "  [FAIL] {}",
+            msg.as_ref()
+        );
+    }
+
+    /// Passes if the condition is true, fails otherwise
+    fn check(&mut self, ok: bool, pass: impl AsRef<str>, fail: impl AsRef<str>) -> bool {
+        if ok {
+            self.pass(pass);
+        } else {
+            self.fail(fail);
+        }
+        ok
+    }
+}
+
+impl Doctor {
+    /// Execute command. Fails if any of the checks failed.
+    pub fn execute(&self) -> Result<()> {
+        let mut report = Report::default();
+
+        important!("Tool config");
+        check_tool_config(&mut report);
+
+        important!("Resources");
+        check_resources(&mut report);
+
+        important!("Modules");
+        let names = check_modules(&mut report);
+
+        important!("Profiles");
+        check_profiles(&mut report, &names);
+
+        important!("Project");
+        self.check_project(&mut report);
+
+        info!(
+            "{} passed, {} warnings, {} failed",
+            report.passed, report.warnings, report.failures
+        );
+
+        if report.failures > 0 || (self.strict && report.warnings > 0) {
+            return Err(anyhow!(
+                "{} checks failed, {} warnings",
+                report.failures,
+                report.warnings
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_project(&self, report: &mut Report) {
+        let path = crate::WORKDIR.join(&self.config);
+        if !path.is_file() {
+            info!("  No mod config at {}, skipping", path.display());
+            return;
+        }
+
+        let modconfig = match ModConfig::load(&self.config) {
+            Ok(modconfig) => modconfig,
+            Err(err) => {
+                report.fail(err.to_string());
+                return;
+            }
+        };
+        report.pass(format!("Mod config loaded: {}", path.display()));
+
+        let uproject = modconfig.uproject();
+        report.check(
+            uproject.is_file(),
+            format!("Unreal project found: {}", uproject.display()),
+            format!("Unreal project not found: {}", uproject.display()),
+        );
+
+        let content = content_files(&modconfig.wd().join("Content"));
+
+        for target in modconfig.targets() {
+            if target.name().ends_with("_P") {
+                report.pass(format!("Pak name {} ends with _P", target.name()));
+            } else {
+                report.warn(format!(
+                    "Pak name {} doesn't end with _P, it may be overridden by the game's paks",
+                    target.name()
+                ));
+            }
+
+            for include in target.includes() {
+                let found = match PathPattern::new(include) {
+                    Ok(pattern) => content.iter().any(|f| pattern.matches(f)),
+                    Err(err) => {
+                        report.fail(format!("Invalid include {}: {err}", include.display()));
+                        continue;
+                    }
+                };
+
+                if found {
+                    report.pass(format!("Include found in Content: {}", include.display()));
+                } else {
+                    report.warn(format!(
+                        "Include not found in Content: {}",
+                        include.display()
+                    ));
+                }
+            }
+
+            for copy in target.copies() {
+                let source = modconfig.wd().join(native_path(copy.source()));
+                if !source.exists() && !copy.is_default() {
+                    report.warn(format!(
+                        "Raw content not found: {}",
+                        copy.source().display()
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn check_tool_config(report: &mut Report) {
+    if !report.check(
+        ToolConfig::check(),
+        format!(
+            "Tool config found: {} ({})",
+            ToolConfig::path().display(),
+            ToolConfig::source()
+        ),
+        format!("Tool config not found: {}", ToolConfig::path().display()),
+    ) {
+        return;
+    }
+
+    let config = match ToolConfig::load() {
+        Ok(config) => config,
+        Err(err) => {
+            report.fail(err.to_string());
+            return;
+        }
+    };
+
+    let engine_dir = config.engine();
+    if report.check(
+        engine_dir.is_dir(),
+        format!("Engine directory exists: {}", engine_dir.display()),
+        format!("Engine directory not found: {}", engine_dir.display()),
+    ) {
+        for tool in [engine::UAT, engine::UNREALPAK] {
+            let path = native_path(engine_dir.join(tool));
+            report.check(
+                path.is_file(),
+                format!("Found {}", path.display()),
+                format!("Not found: {}", path.display()),
+            );
+        }
+
+        // Only needed to compile the C++ code of the projects
+        let ubt = native_path(engine_dir.join(engine::UBT));
+        if ubt.is_file() {
+            report.pass(format!("Found {}", ubt.display()));
+        } else {
+            report.warn(format!(
+                "Not found: {} (needed to build projects with C++ code)",
+                ubt.display()
+            ));
+        }
+
+        match engine::verify(engine_dir) {
+            Ok(engine) => report.pass(format!("Engine version is {}", engine.version())),
+            Err(err @ engine::EngineError::MissingTool(_)) => verbose!("  {err}"),
+            Err(err) => report.fail(err.to_string()),
+        }
+    }
+
+    let moddir = config.moddir();
+    if report.check(
+        moddir.is_dir(),
+        format!("Mods directory exists: {}", moddir.display()),
+        format!("Mods directory not found: {}", moddir.display()),
+    ) && !crate::utils::dry_run()
+    {
+        let probe = moddir.join(".cvmodtool-write-test");
+        let writable = std::fs::write(&probe, b"").is_ok();
+        let _ = std::fs::remove_file(&probe);
+        report.check(
+            writable,
+            "Mods directory is writable",
+            "Mods directory is not writable",
+        );
+    }
+}
+
+fn check_resources(report: &mut Report) {
+    let dir = resources::dir();
+    if !report.check(
+        dir.is_dir(),
+        format!("Resources directory exists: {}", dir.display()),
+        format!("Resources directory not found: {}", dir.display()),
+    ) {
+        return;
+    }
+
+    let version_file = resources::version_file();
+    match std::fs::read_to_string(&version_file) {
+        Ok(version) => {
+            let version = version.trim();
+            let valid = version.split('.').count() == 3
+                && version.split('.').all(|p| p.parse::<u64>().is_ok());
+
+            if !valid {
+                report.fail(format!("Invalid resources version: {version}"));
+            } else if version != super::PKG_VERSION {
+                report.warn(format!(
+                    "Resources version {version} doesn't match the tool version {}",
+                    super::PKG_VERSION
+                ));
+            } else {
+                report.pass(format!("Resources version is {version}"));
+            }
+        }
+        Err(_) => report.warn(format!(
+            "Resources version file not found: {}",
+            version_file.display()
+        )),
+    }
+}
+
+/// Returns the names of the modules loaded
+fn check_modules(report: &mut Report) -> Vec<String> {
+    let files = match modules::config_files() {
+        Ok(files) => files,
+        Err(err) => {
+            report.fail(format!("Failed to read the modules directory: {err}"));
+            return vec![];
+        }
+    };
+
+    let mut loaded = vec![];
+    for file in files {
+        match modules::Module::load(file.clone()) {
+            Ok(module) => loaded.push(module),
+            Err(err) => report.fail(format!("{}: {err}", file.display())),
+        }
+    }
+
+    let names: Vec<String> = loaded.iter().map(|m| m.name().to_string()).collect();
+    for module in &loaded {
+        let missing: Vec<String> = module
+            .dependencies()
+            .iter()
+            .filter(|d| !names.contains(d))
+            .cloned()
+            .collect();
+
+        if missing.is_empty() {
+            report.pass(format!("Module {} loaded", module.name()));
+        } else {
+            report.fail(format!(
+                "Module {} depends on missing modules: {}",
+                module.name(),
+                super::modules::list(&missing)
+            ));
+        }
+    }
+
+    names
+}
+
+fn check_profiles(report: &mut Report, modules: &[String]) {
+    // The tool config may define profiles too
+    let profiles = match profiles::load_merged() {
+        Ok(profiles) => profiles,
+        Err(err) => {
+            report.fail(err.to_string());
+            return;
+        }
+    };
+
+    let mut names: Vec<&String> = profiles.keys().collect();
+    names.sort_unstable();
+
+    for name in names {
+        let missing: Vec<String> = profiles[name]
+            .iter()
+            .filter(|m| !modules.contains(m))
+            .cloned()
+            .collect();
+
+        if missing.is_empty() {
+            report.pass(format!("Profile {name} is valid"));
+        } else {
+            report.fail(format!(
+                "Profile {name} references missing modules: {}",
+                super::modules::list(&missing)
+            ));
+        }
+    }
+}
+
+/// Content relative paths of the files
+fn content_files(content: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+
+    for entry in WalkDir::new(content).into_iter().filter_map(|e| e.ok()) {
+        if let Ok(relative) = entry.path().strip_prefix(content) {
+            if relative.as_os_str().is_empty() {
+                continue;
+            }
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    files
+}
@@ -1,7 +1,9 @@
 use crate::config::{ModConfig, ToolConfig};
+use crate::mods::{conflicts, ModManager};
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 /// Copy the mod's pak file into the game's content directory
 #[derive(Parser)]
@@ -12,9 +14,22 @@ pub struct Install {
     /// Mod configuration file to use
     #[arg(short, long, default_value = "cvmod.toml")]
     config: PathBuf,
+
+    /// Don't check for conflicts with other installed mods
+    #[arg(long)]
+    no_check: bool,
+
+    /// Only install the pak target with this name
+    #[arg(short, long, conflicts_with = "pak")]
+    target: Option<String>,
 }
 
 impl Install {
+    /// Command with the default arguments and the given mod config
+    pub fn new(config: PathBuf) -> Self {
+        Self::parse_from([OsString::from("install"), "--config".into(), config.into()])
+    }
+
     /// Pak file
     pub fn pak(&self) -> &Option<PathBuf> {
         &self.pak
@@ -25,38 +40,86 @@ impl Install {
         &self.config
     }
 
+    pub fn no_check(&self) -> bool {
+        self.no_check
+    }
+
+    /// Selected pak target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
     /// Execute command
     pub fn execute(&self) -> Result<()> {
         important!("Installing mod package...");
 
-        let pakfile = {
+        let (pakfiles, modconfig) = {
             if let Some(pak) = self.pak() {
-                pak.to_owned()
+                (vec![pak.to_owned()], None)
             } else {
                 verbose!("Loading mod config...");
                 let modconfig = ModConfig::load(self.config())?;
-                modconfig.pakfile()
+                let pakfiles = modconfig
+                    .select_targets(self.target())?
+                    .iter()
+                    .map(|t| modconfig.pakfile(t))
+                    .collect();
+                (pakfiles, Some(modconfig))
             }
         };
 
-        if !pakfile.is_file() {
+        if let Some(missing) = pakfiles.iter().find(|p| !p.is_file()) {
             return Err(anyhow!(
-                "Package file was not found! Make sure to package the project first."
+                "Package file ({}) was not found! Make sure to package the project first.",
+                missing.display()
             ));
         }
 
         verbose!("Loading tool config...");
         let config = ToolConfig::load()?;
 
-        let pakfilename = pakfile
-            .file_name()
-            .ok_or_else(|| anyhow!("Failed to get the .pak file name"))?;
-        let target = config.moddir().join(pakfilename);
-        if let Err(err) = std::fs::copy(pakfile, &target) {
-            return Err(anyhow!("Failed to copy .pak file: {}", err));
+        let mut manager = ModManager::new(config.moddir())?;
+        let project = modconfig
+            .as_ref()
+            .map(|m| (m.project().as_str(), m.wd().as_path()));
+
+        for pakfile in &pakfiles {
+            let target = manager.install(pakfile, project)?;
+
+            if !self.no_check() {
+                check_conflicts(config.moddir(), &target, pakfile);
+            }
+
+            if !crate::utils::dry_run() {
+                info!("Success! Pak file installed to {}", target.display());
+            }
         }
 
-        info!("Success! Pak file installed to {}", target.display());
         Ok(())
     }
 }
+
+fn check_conflicts(moddir: &Path, target: &Path, source: &Path) {
+    info!("Checking for conflicts...");
+    let mut paks = match crate::mods::find_paks(moddir) {
+        Ok(paks) => paks,
+        Err(err) => {
+            warning!("Failed to read mods directory: {err}");
+            return;
+        }
+    };
+
+    // The pak is not copied yet in dry-run mode, use the source instead
+    let pakfile = if crate::utils::dry_run() {
+        paks.retain(|p| p != target);
+        paks.push(source.to_path_buf());
+        crate::mods::sort_load_order(&mut paks);
+        source
+    } else {
+        target
+    };
+
+    let mut conflicts = conflicts::find(&paks);
+    conflicts.retain(|c| c.involves(pakfile));
+    super::conflicts::report(&conflicts);
+}
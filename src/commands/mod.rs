@@ -1,12 +1,23 @@
 use clap::builder::Styles;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 pub mod build;
+pub mod config;
+pub mod conflicts;
 pub mod create;
+pub mod doctor;
 pub mod install;
+pub mod mods;
+pub mod modules;
 pub mod package;
+pub mod pak;
+pub mod profiles;
+pub mod project;
+pub mod run;
 #[cfg(feature = "updater")]
 pub mod update;
+pub mod watch;
 
 pub const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -33,6 +44,18 @@ pub struct Opts {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Print the planned file operations and tool runs without doing them (not supported by update)
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Fail instead of prompting for input (e.g. when the tool config is missing)
+    #[arg(long, global = true)]
+    no_interactive: bool,
+
+    /// Tool config file to use
+    #[arg(long, global = true, value_name = "PATH")]
+    tool_config: Option<PathBuf>,
+
     #[command(subcommand)]
     subcmd: SubCommand,
 }
@@ -42,6 +65,18 @@ impl Opts {
         self.verbose
     }
 
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn no_interactive(&self) -> bool {
+        self.no_interactive
+    }
+
+    pub fn tool_config(&self) -> &Option<PathBuf> {
+        &self.tool_config
+    }
+
     pub fn subcmd(&self) -> &SubCommand {
         &self.subcmd
     }
@@ -53,6 +88,16 @@ pub enum SubCommand {
     Build(build::Build),
     Package(package::Package),
     Install(install::Install),
+    Run(run::Run),
+    Project(project::Project),
+    Pak(pak::Pak),
+    Conflicts(conflicts::Conflicts),
+    Mods(mods::Mods),
+    Modules(modules::Modules),
+    Profiles(profiles::Profiles),
+    Config(config::Config),
+    Doctor(doctor::Doctor),
+    Watch(watch::Watch),
     #[cfg(feature = "updater")]
     Update(update::Update),
 }
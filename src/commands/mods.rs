@@ -0,0 +1,99 @@
+use crate::config::ToolConfig;
+use crate::mods::ModManager;
+use crate::utils::{format_size, format_time};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+/// Manage the installed mods
+#[derive(Parser)]
+pub struct Mods {
+    #[command(subcommand)]
+    subcmd: ModsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ModsCommand {
+    /// List the installed mods
+    List,
+    /// Delete an installed mod
+    Uninstall(Target),
+    /// Move a disabled mod back into the mods folder
+    Enable(Target),
+    /// Move a mod into the disabled folder
+    Disable(Target),
+}
+
+#[derive(Parser)]
+pub struct Target {
+    /// Name of the pak file (the .pak extension is optional)
+    name: String,
+}
+
+impl Mods {
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        verbose!("Loading tool config...");
+        let config = ToolConfig::load()?;
+        let mut manager = ModManager::new(config.moddir())?;
+
+        match &self.subcmd {
+            ModsCommand::List => list(&manager),
+            ModsCommand::Uninstall(target) => {
+                let path = manager.uninstall(&target.name)?;
+                if !crate::utils::dry_run() {
+                    info!("Success! Deleted {}", path.display());
+                }
+                Ok(())
+            }
+            ModsCommand::Enable(target) => {
+                let path = manager.enable(&target.name)?;
+                if !crate::utils::dry_run() {
+                    info!("Success! Enabled {}", path.display());
+                }
+                Ok(())
+            }
+            ModsCommand::Disable(target) => {
+                let path = manager.disable(&target.name)?;
+                if !crate::utils::dry_run() {
+                    info!("Success! Disabled {}", path.display());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn list(manager: &ModManager) -> Result<()> {
+    let mods = manager.list()?;
+    if mods.is_empty() {
+        info!("No mods installed.");
+        return Ok(());
+    }
+
+    important!("Installed mods:");
+    for installed in &mods {
+        let state = if installed.enabled { "" } else { " (disabled)" };
+        let modified = installed.modified.map(format_time).unwrap_or_default();
+        info!("  {}{state}", installed.name());
+        println!(
+            "    Size: {}, Modified: {modified}",
+            format_size(installed.size)
+        );
+
+        if let Some(record) = &installed.record {
+            let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(record.installed);
+            println!(
+                "    Project: {} ({}), Installed: {}",
+                record.project,
+                record.path.display(),
+                format_time(time)
+            );
+        }
+    }
+
+    verbose!(
+        "Disabled mods are kept in {}",
+        manager.disabled_dir().display()
+    );
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::resources::modules;
+use crate::resources::REPLACE;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+/// List the available modules
+#[derive(Parser)]
+pub struct Modules {
+    /// Only show this module
+    name: Option<String>,
+}
+
+impl Modules {
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        let mut modules = modules::load()?;
+        modules.sort_by(|a, b| a.name().cmp(b.name()));
+
+        if let Some(name) = &self.name {
+            modules.retain(|m| m.name() == name);
+            if modules.is_empty() {
+                return Err(anyhow!("Module not found: {name}"));
+            }
+        }
+
+        for module in &modules {
+            let files = module.files(REPLACE)?.len();
+            let pakinclude = module
+                .pakinclude()
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>();
+
+            important!("{}", module.name());
+            info!("  Dependencies: {}", list(module.dependencies()));
+            info!("  Pak includes: {}", list(&pakinclude));
+            info!("  Credits: {}", list(module.credits()));
+            info!("  Files: {files}");
+            verbose!("  Path: {}", module.path().display());
+        }
+
+        Ok(())
+    }
+}
+
+pub fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
@@ -1,11 +1,22 @@
-use crate::config::{ModConfig, ModConfigError, ToolConfig, ToolConfigError};
+use crate::config::{
+    CopyEntry, ModConfig, ModConfigError, PakTarget, StagedFile, Staging, StagingError, ToolConfig,
+    ToolConfigError,
+};
+use crate::pak::{PakError, PakWriter};
+use crate::pattern::PathPattern;
+use crate::utils::{content_hash, native_path, path_key};
 use anyhow::{anyhow, Result};
 use clap::Parser;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf, StripPrefixError};
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 use thiserror::Error;
 use walkdir::WalkDir;
 
+/// Content directory inside the pak
+const PAK_CONTENT_DIR: &str = "CodeVein/Content";
+
 /// Package a mod project into a .pak file
 #[derive(Parser)]
 pub struct Package {
@@ -20,9 +31,26 @@ pub struct Package {
     /// Don't compress the .pak file
     #[arg(long)]
     no_compress: bool,
+
+    /// Use the engine's UnrealPak instead of the built-in pak writer
+    #[arg(long)]
+    unrealpak: bool,
+
+    /// Only package the pak target with this name
+    #[arg(short, long)]
+    target: Option<String>,
+
+    /// Recopy every file and recreate the .pak file even if nothing changed
+    #[arg(short, long)]
+    force: bool,
 }
 
 impl Package {
+    /// Command with the default arguments and the given mod config
+    pub fn new(config: PathBuf) -> Self {
+        Self::parse_from([OsString::from("package"), "--config".into(), config.into()])
+    }
+
     /// Mod configuration file relative path
     pub fn config(&self) -> &PathBuf {
         &self.config
@@ -36,98 +64,305 @@ impl Package {
         self.no_compress
     }
 
+    pub fn unrealpak(&self) -> bool {
+        self.unrealpak
+    }
+
+    /// Selected pak target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
     /// Execute command
     pub fn execute(&self) -> Result<(), PackageError> {
         important!("Packaging mod project...");
 
         verbose!("Loading mod config...");
         let modconfig = ModConfig::load(self.config())?;
-        verbose!("Loading tool config...");
-        let config = ToolConfig::load()?;
+        let targets = modconfig.select_targets(self.target())?;
+
+        for target in &targets {
+            if targets.len() > 1 {
+                important!("Packaging {}...", target.name());
+            }
+            self.package_target(&modconfig, target)?;
+        }
 
+        Ok(())
+    }
+
+    fn package_target(
+        &self,
+        modconfig: &ModConfig,
+        target: &PakTarget,
+    ) -> Result<(), PackageError> {
         verbose!("Generating paths...");
-        let packagedir = modconfig.wd().join(modconfig.packagedir());
-        let pakdir = packagedir.join(modconfig.pakname());
-        let pakfile = modconfig.pakfile();
-        let pak_content_dir = pakdir.join("CodeVein\\Content");
-        let cooked_content_dir = modconfig.wd().join(format!(
-            "Saved\\Cooked\\WindowsNoEditor\\{}\\Content",
-            modconfig.project()
-        ));
-        let precooked_content_dir = modconfig.wd().join("ContentPreCooked");
+        let packagedir = modconfig.wd().join(native_path(modconfig.packagedir()));
+        let pakdir = packagedir.join(target.name());
+        let pakfile = modconfig.pakfile(target);
+        let stagefile = packagedir.join(format!("{}.staging.toml", target.name()));
+        let cooked_content_dir = modconfig.cooked_content_dir();
+        let compress = target.compress() && !self.no_compress();
+
+        let mut staging = if self.force() {
+            Staging::new(&stagefile)
+        } else {
+            Staging::load(&stagefile)?
+        };
+        let mut changed = true;
 
         if !self.no_copy() {
             if !cooked_content_dir.is_dir() {
                 return Err(PackageError::NoCookedContent);
             }
 
-            if pakdir.is_dir() {
+            // Without a staging manifest the state of the staged files is unknown
+            if staging.is_empty() && pakdir.is_dir() {
                 info!("Cleaning up old files...");
-                std::fs::remove_dir_all(&pakdir).map_err(PackageError::CleanFailed)?;
+                if crate::utils::dry_run() {
+                    planned!("Delete directory: {}", pakdir.display());
+                } else {
+                    std::fs::remove_dir_all(&pakdir).map_err(PackageError::CleanFailed)?;
+                }
             }
 
-            std::fs::create_dir_all(&pak_content_dir).map_err(PackageError::PkgDirCreateFailed)?;
+            if !crate::utils::dry_run() {
+                std::fs::create_dir_all(&pakdir).map_err(PackageError::PkgDirCreateFailed)?;
+            }
 
-            info!("Copying package files...");
+            info!("Collecting package files...");
+            let mut files = BTreeMap::new();
+            collect_cooked(target, &cooked_content_dir, &mut files)?;
+            for copy in target.copies() {
+                collect_raw(copy, modconfig.wd(), &mut files)?;
+            }
 
-            // Copy cooked content
-            let walker = WalkDir::new(&cooked_content_dir).into_iter();
-            for entry in walker.filter_map(|e| e.ok()) {
-                let absolute = entry.path();
-                if !absolute.is_file() {
-                    continue;
-                }
+            info!("Syncing package files...");
+            changed = sync_files(&mut staging, &files, &pakdir)?;
 
-                let relative = absolute.strip_prefix(&cooked_content_dir)?;
-                let str_relative = path_same_separator(relative); // Workaround for path starts_with issues
-
-                if modconfig.includes().iter().any(|i| {
-                    let str_i = path_same_separator(i); // Workaround for path starts_with issues
-                    str_relative.starts_with(&str_i)
-                }) {
-                    verbose!("  Copying file: {}", relative.display());
-                    let target = pak_content_dir.join(relative);
-                    let parent = target.parent().ok_or(PackageError::NoParent)?;
-                    std::fs::create_dir_all(parent).map_err(PackageError::ParentCreateFailed)?;
-                    std::fs::copy(absolute, target).map_err(PackageError::CopyFailed)?;
-                }
+            if changed {
+                staging.set_packed(None);
+            }
+            if !crate::utils::dry_run() {
+                staging.save()?;
             }
+        }
 
-            // Copy raw content
-            let walker = WalkDir::new(&precooked_content_dir).into_iter();
-            for entry in walker.filter_map(|e| e.ok()) {
-                let absolute = entry.path();
-                if !absolute.is_file() {
-                    continue;
-                }
+        if !changed && staging.is_packed(compress) && pakfile.is_file() {
+            info!(
+                "No changes! Pak file is up to date at {}",
+                pakfile.display()
+            );
+            return Ok(());
+        }
+
+        if crate::utils::dry_run() {
+            planned!("Create pak file: {}", pakfile.display());
+            return Ok(());
+        }
 
-                let relative = absolute.strip_prefix(&precooked_content_dir)?;
+        if self.unrealpak() {
+            verbose!("Loading tool config...");
+            let config = ToolConfig::load()?;
 
-                verbose!("  Copying file: {}", relative.display());
-                let target = pak_content_dir.join(relative);
-                let parent = target.parent().ok_or(PackageError::NoParent)?;
-                std::fs::create_dir_all(parent).map_err(PackageError::ParentCreateFailed)?;
-                std::fs::copy(absolute, target).map_err(PackageError::CopyFailed)?;
-            }
+            info!("Running UnrealPak...");
+            run_upak(&config, &packagedir, &pakdir, &pakfile, compress)
+                .map_err(PackageError::UnrealPak)?;
+        } else {
+            info!("Creating pak file...");
+            write_pak(&pakdir, &pakfile, compress)?;
         }
 
-        info!("Running UnrealPak...");
-        run_upak(
-            &config.upak(),
-            &packagedir,
-            &pakdir,
-            &pakfile,
-            !self.no_compress(),
-        )
-        .map_err(PackageError::UnrealPak)?;
+        if !self.no_copy() {
+            staging.set_packed(Some(compress));
+            staging.save()?;
+        }
 
         info!("Success! Pak file created at {}", pakfile.display());
         Ok(())
     }
 }
 
+/// Collects the included cooked files (pak relative path -> source path)
+fn collect_cooked(
+    target: &PakTarget,
+    cooked_content_dir: &Path,
+    files: &mut BTreeMap<String, PathBuf>,
+) -> Result<(), PackageError> {
+    let includes = patterns(target.includes())?;
+    let excludes = patterns(target.excludes())?;
+    let mut matched = vec![0usize; includes.len()];
+
+    let walker = WalkDir::new(cooked_content_dir).into_iter();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let absolute = entry.path();
+        if !absolute.is_file() {
+            continue;
+        }
+
+        let relative = absolute.strip_prefix(cooked_content_dir)?;
+
+        let mut included = false;
+        for (pattern, count) in includes.iter().zip(matched.iter_mut()) {
+            if pattern.matches(relative) {
+                *count += 1;
+                included = true;
+            }
+        }
+
+        if !included {
+            continue;
+        }
+
+        if excludes.iter().any(|e| e.matches(relative)) {
+            verbose!("  Excluding file: {}", relative.display());
+            continue;
+        }
+
+        let relative = Path::new(PAK_CONTENT_DIR).join(relative);
+        files.insert(path_key(&relative), absolute.to_path_buf());
+    }
+
+    // Report includes which matched nothing (likely a typo)
+    for (include, count) in target.includes().iter().zip(matched) {
+        if count == 0 {
+            warning!("Include matched no cooked files: {}", include.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the raw files (pak relative path -> source path)
+fn collect_raw(
+    copy: &CopyEntry,
+    wd: &Path,
+    files: &mut BTreeMap<String, PathBuf>,
+) -> Result<(), PackageError> {
+    let source = wd.join(native_path(copy.source()));
+    if !source.exists() {
+        if copy.is_default() {
+            verbose!(
+                "  Skipping missing raw content: {}",
+                copy.source().display()
+            );
+        } else {
+            warning!("  Raw content not found: {}", copy.source().display());
+        }
+        return Ok(());
+    }
+
+    let target = copy.target().map(native_path);
+    if let Some(target) = &target {
+        if !target
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(PackageError::InvalidCopyTarget(target.to_owned()));
+        }
+    }
+
+    if source.is_file() {
+        let target = match target {
+            Some(target) => target,
+            None => {
+                Path::new(PAK_CONTENT_DIR).join(source.file_name().ok_or(PackageError::NoParent)?)
+            }
+        };
+        files.insert(path_key(&target), source);
+        return Ok(());
+    }
+
+    let target_dir = target.unwrap_or_else(|| PathBuf::from(PAK_CONTENT_DIR));
+
+    let walker = WalkDir::new(&source).into_iter();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let absolute = entry.path();
+        if !absolute.is_file() {
+            continue;
+        }
+
+        let relative = target_dir.join(absolute.strip_prefix(&source)?);
+        files.insert(path_key(&relative), absolute.to_path_buf());
+    }
+
+    Ok(())
+}
+
+/// Syncs the files into the staging directory. Returns true if anything changed.
+fn sync_files(
+    staging: &mut Staging,
+    files: &BTreeMap<String, PathBuf>,
+    pakdir: &Path,
+) -> Result<bool, PackageError> {
+    let mut changed = false;
+
+    // Remove files which are no longer packaged
+    for relative in staging.files() {
+        if files.contains_key(&relative) {
+            continue;
+        }
+
+        remove_file(&pakdir.join(native_path(&relative)), &relative)?;
+        staging.remove(&relative);
+        changed = true;
+    }
+
+    for (relative, source) in files {
+        let target = pakdir.join(native_path(relative));
+        let metadata = std::fs::metadata(source).map_err(PackageError::CopyFailed)?;
+        if target.is_file() && staging.is_unchanged(relative, &metadata) {
+            continue;
+        }
+
+        // Timestamps change on every cook, check the content as well
+        let data = std::fs::read(source).map_err(PackageError::CopyFailed)?;
+        let hash = content_hash(&data);
+        let same = staging.hash(relative) == Some(&hash);
+
+        if !(same && target.is_file()) {
+            copy_file(&data, &target, relative)?;
+            changed = true;
+        }
+
+        staging.insert(relative.to_owned(), StagedFile::new(&metadata, hash));
+    }
+
+    Ok(changed)
+}
+
+fn copy_file(data: &[u8], target: &Path, relative: &str) -> Result<(), PackageError> {
+    if crate::utils::dry_run() {
+        planned!("Copy file: {}", relative);
+        return Ok(());
+    }
+
+    verbose!("  Copying file: {}", relative);
+    let parent = target.parent().ok_or(PackageError::NoParent)?;
+    std::fs::create_dir_all(parent).map_err(PackageError::ParentCreateFailed)?;
+    std::fs::write(target, data).map_err(PackageError::CopyFailed)?;
+    Ok(())
+}
+
+fn remove_file(target: &Path, relative: &str) -> Result<(), PackageError> {
+    if crate::utils::dry_run() {
+        planned!("Delete file: {}", relative);
+        return Ok(());
+    }
+
+    verbose!("  Removing file: {}", relative);
+    if target.is_file() {
+        std::fs::remove_file(target).map_err(PackageError::CleanFailed)?;
+    }
+    Ok(())
+}
+
 fn run_upak(
-    upak: &Path,
+    config: &ToolConfig,
     packagedir: &Path,
     pakdir: &Path,
     pakfile: &Path,
@@ -138,19 +373,19 @@ fn run_upak(
     // Create filelist.txt
     if let Err(err) = std::fs::write(
         &filelist,
-        format!("\"{}\\*.*\" \"..\\..\\..\\*.*\" ", pakdir.display()),
+        format!("\"{}\\*.*\" \"..\\..\\..\\*.*\" ", config.tool_path(pakdir)),
     ) {
         return Err(anyhow!("Failed to create filelist.txt: {err}"));
     }
 
     // Run UnrealPak
-    let mut command = Command::new(upak);
+    let mut command = config.command(&config.upak()?);
     command
         .stdin(Stdio::null())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .arg(pakfile)
-        .arg(format!("-Create={}", filelist.display()));
+        .arg(config.tool_path(pakfile))
+        .arg(format!("-Create={}", config.tool_path(&filelist)));
 
     if compress {
         command.arg("-compress");
@@ -158,7 +393,7 @@ fn run_upak(
 
     let mut child = command
         .spawn()
-        .map_err(|_| anyhow!("UnrealPak failed to start!"))?;
+        .map_err(|e| anyhow!("UnrealPak failed to start! ({e})"))?;
 
     let exitcode = child.wait()?;
     if !exitcode.success() {
@@ -168,11 +403,39 @@ fn run_upak(
     Ok(())
 }
 
-fn path_same_separator<P: AsRef<Path>>(path: P) -> String {
-    path.as_ref()
-        .to_str()
-        .expect("Path was not a valid string!")
-        .replace('/', "\\")
+fn write_pak(pakdir: &Path, pakfile: &Path, compress: bool) -> Result<(), PackageError> {
+    if !pakdir.is_dir() {
+        return Err(PackageError::NoPackageFiles);
+    }
+
+    // Sort the files so the output is deterministic
+    let mut files = vec![];
+    for entry in WalkDir::new(pakdir).sort_by_file_name() {
+        let entry = entry.map_err(|e| PakError::Io(e.into()))?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+
+    let mut writer = PakWriter::create(pakfile, compress)?;
+    for file in files {
+        let relative = file.strip_prefix(pakdir)?;
+        let data = std::fs::read(&file).map_err(PakError::Io)?;
+        writer.add(&relative.to_string_lossy(), &data)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+fn patterns(paths: &[PathBuf]) -> Result<Vec<PathPattern>, PackageError> {
+    paths
+        .iter()
+        .map(|p| {
+            PathPattern::new(p)
+                .map_err(|e| PackageError::InvalidPattern(p.display().to_string(), e))
+        })
+        .collect()
 }
 
 #[derive(Debug, Error)]
@@ -197,4 +460,109 @@ pub enum PackageError {
     CopyFailed(#[source] std::io::Error),
     #[error("UnrealPak failed: {0}")]
     UnrealPak(#[source] anyhow::Error),
+    #[error("Invalid copy target (must be a relative path inside the pak): {0}")]
+    InvalidCopyTarget(PathBuf),
+    #[error("No files to package! Make sure to copy the cooked content first.")]
+    NoPackageFiles,
+    #[error("Invalid include/exclude pattern \"{0}\": {1}")]
+    InvalidPattern(String, #[source] glob::PatternError),
+    #[error("Failed to update staging manifest: {0}")]
+    Staging(#[from] StagingError),
+    #[error("Failed to create pak file: {0}")]
+    Pak(#[from] PakError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sync_files() {
+        let dir = temp_dir("cvmodtool_test_sync");
+        let pakdir = dir.join("Pak");
+        let source = dir.join("A.uasset");
+        fs::write(&source, b"a").unwrap();
+        fs::write(dir.join("B.uasset"), b"b").unwrap();
+
+        let mut files = BTreeMap::from([
+            ("A.uasset".to_string(), source.clone()),
+            ("B.uasset".to_string(), dir.join("B.uasset")),
+        ]);
+        let mut staging = Staging::new(dir.join("staging.toml"));
+
+        assert!(sync_files(&mut staging, &files, &pakdir).unwrap());
+        assert_eq!(fs::read(pakdir.join("A.uasset")).unwrap(), b"a");
+
+        // Nothing changed
+        assert!(!sync_files(&mut staging, &files, &pakdir).unwrap());
+
+        // Only the modification time changed (recooked): rehashed but not copied
+        let modified = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let metadata = fs::metadata(&source).unwrap();
+        assert!(!staging.is_unchanged("A.uasset", &metadata));
+        assert!(!sync_files(&mut staging, &files, &pakdir).unwrap());
+        assert!(staging.is_unchanged("A.uasset", &metadata));
+
+        // Content changed
+        fs::write(&source, b"a2").unwrap();
+        assert!(sync_files(&mut staging, &files, &pakdir).unwrap());
+        assert_eq!(fs::read(pakdir.join("A.uasset")).unwrap(), b"a2");
+
+        // Files no longer packaged are removed from the staging
+        files.remove("B.uasset");
+        assert!(sync_files(&mut staging, &files, &pakdir).unwrap());
+        assert!(!pakdir.join("B.uasset").exists());
+        assert_eq!(staging.files(), ["A.uasset"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_package_compression() {
+        let dir = temp_dir("cvmodtool_test_package");
+        let maps = dir.join("Saved/Cooked/WindowsNoEditor/Test/Content/Maps");
+        fs::create_dir_all(&maps).unwrap();
+        fs::write(maps.join("work.umap"), [7; 10_000]).unwrap();
+        fs::write(
+            dir.join("cvmod.toml"),
+            "project = 'Test'\npakname = 'Z_Test_P'\nincludes = ['Maps']\n",
+        )
+        .unwrap();
+
+        let mut package = Package::new(dir.join("cvmod.toml"));
+        let pakfile = dir.join("Package/Z_Test_P.pak");
+        let stagefile = dir.join("Package/Z_Test_P.staging.toml");
+
+        package.execute().unwrap();
+        assert!(Staging::load(&stagefile).unwrap().is_packed(true));
+        let compressed = fs::metadata(&pakfile).unwrap().len();
+
+        // Nothing changed: the pak file is not recreated
+        fs::write(&pakfile, b"old").unwrap();
+        package.execute().unwrap();
+        assert_eq!(fs::read(&pakfile).unwrap(), b"old");
+
+        // Toggling the compression recreates it
+        package.no_compress = true;
+        package.execute().unwrap();
+        assert!(Staging::load(&stagefile).unwrap().is_packed(false));
+        assert!(fs::metadata(&pakfile).unwrap().len() > compressed);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
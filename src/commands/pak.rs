@@ -0,0 +1,191 @@
+use crate::pak::{self, PakReader};
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use glob::{MatchOptions, Pattern};
+use std::path::{Component, Path, PathBuf};
+
+/// Inspect existing .pak files
+#[derive(Parser)]
+pub struct Pak {
+    #[command(subcommand)]
+    subcmd: PakCommand,
+}
+
+#[derive(Subcommand)]
+pub enum PakCommand {
+    List(List),
+    Extract(Extract),
+}
+
+impl Pak {
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        match &self.subcmd {
+            PakCommand::List(cmd) => cmd.execute(),
+            PakCommand::Extract(cmd) => cmd.execute(),
+        }
+    }
+}
+
+/// List the contents of a .pak file
+#[derive(Parser)]
+pub struct List {
+    /// Pak file to list
+    pak: PathBuf,
+
+    /// Only list entries matching these glob patterns
+    #[arg(short, long, num_args(1..))]
+    filter: Vec<String>,
+}
+
+impl List {
+    /// Pak file
+    pub fn pak(&self) -> &PathBuf {
+        &self.pak
+    }
+
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        let filter = Filter::new(&self.filter)?;
+        let reader = PakReader::open(self.pak())?;
+
+        important!("{}", self.pak().display());
+        info!("Version: {}", reader.version());
+        info!("Mount point: {}", reader.mount_point());
+        info!("Entries: {}", reader.entries().len());
+        verbose!("Index hash: {}", pak::hash_hex(reader.index_hash()));
+
+        let mut total_size = 0;
+        let mut total_stored = 0;
+        let mut count = 0;
+        for (name, entry) in reader.entries() {
+            if !filter.matches(name) {
+                continue;
+            }
+
+            println!(
+                "{:>12} {:>12} {:<6} {} {}",
+                entry.uncompressed_size,
+                entry.size,
+                entry.compression.to_string(),
+                pak::hash_hex(&entry.hash),
+                name
+            );
+
+            total_size += entry.uncompressed_size;
+            total_stored += entry.size;
+            count += 1;
+        }
+
+        info!("{count} entries, {total_size} bytes ({total_stored} bytes stored)");
+        Ok(())
+    }
+}
+
+/// Extract the contents of a .pak file
+#[derive(Parser)]
+pub struct Extract {
+    /// Pak file to extract
+    pak: PathBuf,
+
+    /// Directory to extract into [default: name of the pak file]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Only extract entries matching these glob patterns
+    #[arg(short, long, num_args(1..))]
+    filter: Vec<String>,
+}
+
+impl Extract {
+    /// Pak file
+    pub fn pak(&self) -> &PathBuf {
+        &self.pak
+    }
+
+    /// Output directory
+    pub fn output(&self) -> PathBuf {
+        match &self.output {
+            Some(output) => crate::WORKDIR.join(output),
+            None => {
+                let stem = self.pak().file_stem().unwrap_or_default();
+                crate::WORKDIR.join(stem)
+            }
+        }
+    }
+
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        important!("Extracting pak file...");
+
+        let filter = Filter::new(&self.filter)?;
+        let mut reader = PakReader::open(self.pak())?;
+        let output = self.output();
+
+        let entries = reader.entries().to_vec();
+        let mut count = 0;
+        for (name, entry) in entries {
+            if !filter.matches(&name) {
+                continue;
+            }
+
+            let Some(relative) = safe_path(&name) else {
+                warning!("Skipping entry with unsafe path: {name}");
+                continue;
+            };
+
+            let target = output.join(relative);
+            if crate::utils::dry_run() {
+                planned!("Extract file: {name} -> {}", target.display());
+                count += 1;
+                continue;
+            }
+
+            verbose!("  Extracting file: {name}");
+            let data = reader.read(&name, &entry)?;
+            let parent = target
+                .parent()
+                .ok_or_else(|| anyhow!("Target file has no parent!"))?;
+            std::fs::create_dir_all(parent)?;
+            std::fs::write(target, data)?;
+            count += 1;
+        }
+
+        info!("Success! Extracted {count} files to {}", output.display());
+        Ok(())
+    }
+}
+
+/// Glob filter for pak entry names
+struct Filter(Vec<Pattern>);
+
+impl Filter {
+    const OPTIONS: MatchOptions = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Pattern::new(&p.replace('\\', "/")))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Invalid filter pattern: {e}"))?;
+        Ok(Self(patterns))
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.0.is_empty() || self.0.iter().any(|p| p.matches_with(name, Self::OPTIONS))
+    }
+}
+
+/// Converts the entry name into a relative path that can't escape the output directory
+fn safe_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Some(path.to_path_buf())
+    } else {
+        None
+    }
+}
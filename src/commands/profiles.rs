@@ -0,0 +1,51 @@
+use super::modules::list;
+use crate::resources::{modules, profiles};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+/// List the available creation profiles
+#[derive(Parser)]
+pub struct Profiles {
+    /// Only show this profile
+    name: Option<String>,
+}
+
+impl Profiles {
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        let profiles = profiles::load_merged()?;
+        let available = modules::load()?;
+
+        let mut names: Vec<&String> = profiles.keys().collect();
+        names.sort_unstable();
+
+        if let Some(name) = &self.name {
+            names.retain(|n| *n == name);
+            if names.is_empty() {
+                return Err(anyhow!("Profile not found: {name}"));
+            }
+        }
+
+        for name in names {
+            let selected = &profiles[name];
+            important!("{name}");
+            info!("  Modules: {}", list(selected));
+
+            // Show the modules that would actually be installed
+            match modules::resolve(selected, &available) {
+                Ok(resolved) => {
+                    let resolved = resolved
+                        .iter()
+                        .map(|m| m.name().to_string())
+                        .collect::<Vec<_>>();
+                    if resolved != *selected {
+                        info!("  Resolved: {}", list(&resolved));
+                    }
+                }
+                Err(err) => warning!("  {err}"),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,620 @@
+use crate::config::{Manifest, ModConfig};
+use crate::resources::modules::{self, Module};
+use crate::utils::content_hash;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+/// Manage the modules of an existing mod project
+#[derive(Parser)]
+pub struct Project {
+    /// Mod configuration file to use
+    #[arg(short, long, default_value = "cvmod.toml", global = true)]
+    config: PathBuf,
+
+    #[command(subcommand)]
+    subcmd: ProjectCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ProjectCommand {
+    Add(Add),
+    Remove(Remove),
+    Upgrade(Upgrade),
+}
+
+impl Project {
+    /// Mod configuration file relative path
+    pub fn config(&self) -> &PathBuf {
+        &self.config
+    }
+
+    /// Execute command
+    pub fn execute(&self) -> Result<()> {
+        match &self.subcmd {
+            ProjectCommand::Add(cmd) => cmd.execute(self.config()),
+            ProjectCommand::Remove(cmd) => cmd.execute(self.config()),
+            ProjectCommand::Upgrade(cmd) => cmd.execute(self.config()),
+        }
+    }
+}
+
+/// Install modules into the project
+#[derive(Parser)]
+pub struct Add {
+    /// Modules to install
+    #[arg(required = true)]
+    modules: Vec<String>,
+
+    /// Don't install the dependencies of the modules automatically
+    #[arg(long)]
+    no_deps: bool,
+}
+
+impl Add {
+    /// Execute command
+    pub fn execute(&self, config: &Path) -> Result<()> {
+        important!("Adding modules to the project...");
+
+        verbose!("Loading mod config...");
+        let mut modconfig = ModConfig::load(config)?;
+        let available = modules::load()?;
+
+        let selected = if self.no_deps {
+            self.modules
+                .iter()
+                .map(|name| find(&available, name).cloned())
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            modules::resolve(&self.modules, &available)?
+        };
+
+        let mut manifest = Manifest::load(modconfig.wd())?;
+        let mut installed = modconfig.modules().to_owned();
+        let mut includes = modconfig.includes().to_owned();
+
+        for module in selected {
+            if installed.iter().any(|m| m == module.name()) {
+                verbose!("Module already installed: {}", module.name());
+                continue;
+            }
+
+            // Projects created by older versions don't record their modules. (Installing
+            // them again would merge the .uproject again and duplicate its entries)
+            if is_present(&module, modconfig.wd(), modconfig.project())? {
+                verbose!("Module already present: {}", module.name());
+                installed.push(module.name().to_string());
+                continue;
+            }
+
+            for dependency in module.dependencies() {
+                if !installed.contains(dependency) && !self.modules.contains(dependency) {
+                    warning!(
+                        "Missing dependency for \"{}\" module: \"{}\"",
+                        module.name(),
+                        dependency
+                    );
+                }
+            }
+
+            module.install(modconfig.wd(), modconfig.project())?;
+            manifest.record(&module, modconfig.project())?;
+
+            for include in module.pakinclude() {
+                if !includes.contains(include) {
+                    includes.push(include.to_owned());
+                }
+            }
+            installed.push(module.name().to_string());
+        }
+
+        modconfig.set_includes(includes);
+        modconfig.set_modules(installed);
+        save(&modconfig, config, &manifest)?;
+
+        info!("Success!");
+        Ok(())
+    }
+}
+
+/// Remove modules from the project. Only files that were not modified are deleted.
+#[derive(Parser)]
+pub struct Remove {
+    /// Modules to remove
+    #[arg(required = true)]
+    modules: Vec<String>,
+
+    /// Remove the modules even if other installed modules depend on them
+    #[arg(long)]
+    force: bool,
+}
+
+impl Remove {
+    /// Execute command
+    pub fn execute(&self, config: &Path) -> Result<()> {
+        important!("Removing modules from the project...");
+
+        verbose!("Loading mod config...");
+        let mut modconfig = ModConfig::load(config)?;
+        let available = modules::load()?;
+
+        for name in &self.modules {
+            if !modconfig.modules().contains(name) {
+                return Err(anyhow!("Module is not installed: {name}"));
+            }
+        }
+
+        let remaining: Vec<String> = modconfig
+            .modules()
+            .iter()
+            .filter(|m| !self.modules.contains(m))
+            .cloned()
+            .collect();
+        let remaining_modules: Vec<&Module> = remaining
+            .iter()
+            .filter_map(|name| available.iter().find(|m| m.name() == name))
+            .collect();
+
+        // Check dependents
+        if !self.force {
+            for module in &remaining_modules {
+                if let Some(dep) = module
+                    .dependencies()
+                    .iter()
+                    .find(|d| self.modules.contains(d))
+                {
+                    return Err(anyhow!(
+                        "\"{}\" module depends on \"{dep}\"! (Use --force to remove anyway)",
+                        module.name()
+                    ));
+                }
+            }
+        }
+
+        // Files still provided by the remaining modules
+        let mut kept = vec![];
+        for module in &remaining_modules {
+            kept.extend(
+                module
+                    .files(modconfig.project())?
+                    .into_iter()
+                    .map(|f| f.target),
+            );
+        }
+
+        let mut manifest = Manifest::load(modconfig.wd())?;
+        for name in &self.modules {
+            let module = find(&available, name)?;
+            info!("Removing module: {name}");
+            remove_files(module, &modconfig, &manifest, &kept)?;
+            manifest.remove_module(name);
+        }
+
+        // Remove includes no longer needed by any module
+        let removed_includes: Vec<&PathBuf> = self
+            .modules
+            .iter()
+            .filter_map(|name| available.iter().find(|m| m.name() == name))
+            .flat_map(|m| m.pakinclude())
+            .filter(|i| !remaining_modules.iter().any(|m| m.pakinclude().contains(i)))
+            .collect();
+        let includes = modconfig
+            .includes()
+            .iter()
+            .filter(|i| !removed_includes.contains(i))
+            .cloned()
+            .collect();
+
+        modconfig.set_includes(includes);
+        modconfig.set_modules(remaining);
+        save(&modconfig, config, &manifest)?;
+
+        info!("Success!");
+        Ok(())
+    }
+}
+
+/// Apply the updates of the installed modules to the project
+#[derive(Parser)]
+pub struct Upgrade {
+    /// Write the new version of locally modified files next to them (with .new extension)
+    #[arg(long)]
+    side_by_side: bool,
+}
+
+/// Result of upgrading the files
+#[derive(Default)]
+struct UpgradeReport {
+    updated: usize,
+    added: usize,
+    deleted: usize,
+    conflicts: Vec<PathBuf>,
+}
+
+impl Upgrade {
+    /// Execute command
+    pub fn execute(&self, config: &Path) -> Result<()> {
+        important!("Upgrading project modules...");
+
+        verbose!("Loading mod config...");
+        let modconfig = ModConfig::load(config)?;
+        let available = modules::load()?;
+        let mut manifest = Manifest::load(modconfig.wd())?;
+
+        if modconfig.modules().is_empty() {
+            return Err(anyhow!(
+                "The mod config doesn't list any installed modules! (Use 'project add' to record them)"
+            ));
+        }
+
+        let mut report = UpgradeReport::default();
+        for name in modconfig.modules() {
+            let module = match find(&available, name) {
+                Ok(module) => module,
+                Err(err) => {
+                    warning!("{err}");
+                    continue;
+                }
+            };
+
+            if manifest.files(name).is_none() {
+                warning!(
+                    "No manifest for \"{name}\" module. Differing files are reported as conflicts."
+                );
+            }
+
+            info!("Upgrading module: {name}");
+            self.upgrade_module(module, &modconfig, &mut manifest, &mut report)?;
+        }
+
+        if crate::utils::dry_run() {
+            planned!("Write manifest");
+        } else {
+            manifest.save()?;
+        }
+
+        info!(
+            "Updated: {}, Added: {}, Deleted: {}, Conflicts: {}",
+            report.updated,
+            report.added,
+            report.deleted,
+            report.conflicts.len()
+        );
+        for conflict in &report.conflicts {
+            warning!("  Conflict: {}", conflict.display());
+        }
+
+        if report.conflicts.is_empty() {
+            info!("Success!");
+        } else if self.side_by_side {
+            warning!("Locally modified files were kept. The new versions were written next to them with .new extension.");
+        } else {
+            warning!("Locally modified files were kept. Use --side-by-side to write out the new versions.");
+        }
+
+        Ok(())
+    }
+
+    fn upgrade_module(
+        &self,
+        module: &Module,
+        modconfig: &ModConfig,
+        manifest: &mut Manifest,
+        report: &mut UpgradeReport,
+    ) -> Result<()> {
+        let project_dir = modconfig.wd();
+        let project = modconfig.project();
+        let name = module.name();
+
+        let files = module.files(project)?;
+        for file in &files {
+            if file.is_merged() {
+                continue;
+            }
+
+            let content = file.content(project)?;
+            let new = content_hash(&content);
+            let old = manifest.hash(name, &file.target).cloned();
+            if old.as_ref() == Some(&new) {
+                continue; // Not changed in the module
+            }
+
+            let target = project_dir.join(&file.target);
+            if !target.is_file() {
+                if old.is_some() {
+                    verbose!("  Skipping locally deleted file: {}", file.target.display());
+                    continue;
+                }
+
+                verbose!("  Adding file: {}", file.target.display());
+                if crate::utils::dry_run() {
+                    planned!("Copy file: {}", target.display());
+                } else {
+                    if let Some(parent) = target.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&target, &content)?;
+                }
+                manifest.set_hash(name, &file.target, new);
+                report.added += 1;
+                continue;
+            }
+
+            let current = content_hash(&std::fs::read(&target)?);
+            if current == new {
+                manifest.set_hash(name, &file.target, new);
+            } else if old.as_ref() == Some(&current) {
+                verbose!("  Updating file: {}", file.target.display());
+                write(&target, &content)?;
+                manifest.set_hash(name, &file.target, new);
+                report.updated += 1;
+            } else {
+                if self.side_by_side {
+                    let mut side = target.clone().into_os_string();
+                    side.push(".new");
+                    write(Path::new(&side), &content)?;
+                }
+                report.conflicts.push(file.target.clone());
+            }
+        }
+
+        // Files that were removed from the module
+        let removed: Vec<PathBuf> = manifest
+            .files(name)
+            .map(|recorded| {
+                recorded
+                    .keys()
+                    .map(PathBuf::from)
+                    .filter(|p| !files.iter().any(|f| Manifest::same_path(&f.target, p)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for path in removed {
+            let target = project_dir.join(&path);
+            let unmodified = match (target.is_file(), manifest.hash(name, &path)) {
+                (true, Some(hash)) => content_hash(&std::fs::read(&target)?) == *hash,
+                _ => false,
+            };
+
+            if unmodified {
+                verbose!("  Deleting file: {}", path.display());
+                delete(project_dir, &target)?;
+                report.deleted += 1;
+            } else if target.is_file() {
+                warning!(
+                    "  Keeping modified file removed from the module: {}",
+                    path.display()
+                );
+            }
+            manifest.remove_file(name, &path);
+        }
+
+        Ok(())
+    }
+}
+
+fn find<'a>(available: &'a [Module], name: &str) -> Result<&'a Module> {
+    available
+        .iter()
+        .find(|m| m.name() == name)
+        .ok_or_else(|| anyhow!("Module not found: {name}"))
+}
+
+/// Checks if all the (not merged) files of the module exist in the project.
+/// Modules with only merged files can't be detected.
+fn is_present(module: &Module, project_dir: &Path, project: &str) -> Result<bool> {
+    let files = module.files(project)?;
+    let mut files = files.iter().filter(|f| !f.is_merged()).peekable();
+    Ok(files.peek().is_some() && files.all(|f| project_dir.join(&f.target).is_file()))
+}
+
+/// Deletes the files installed by the module that were not modified since
+fn remove_files(
+    module: &Module,
+    modconfig: &ModConfig,
+    manifest: &Manifest,
+    kept: &[PathBuf],
+) -> Result<()> {
+    let project_dir = modconfig.wd().as_path();
+    let project = modconfig.project().as_str();
+
+    for file in module.files(project)? {
+        let target = project_dir.join(&file.target);
+        if !target.is_file() {
+            continue;
+        }
+
+        if file.is_merged() {
+            warning!("  Keeping merged file: {}", file.target.display());
+            continue;
+        }
+
+        if kept.contains(&file.target) {
+            verbose!(
+                "  Keeping file used by other modules: {}",
+                file.target.display()
+            );
+            continue;
+        }
+
+        // Compare with the installed version if known, otherwise with the current one
+        let current = content_hash(&std::fs::read(&target)?);
+        let original = match manifest.hash(module.name(), &file.target) {
+            Some(hash) => hash.to_owned(),
+            None => content_hash(&file.content(project)?),
+        };
+        if current != original {
+            warning!("  Keeping modified file: {}", file.target.display());
+            continue;
+        }
+
+        verbose!("  Deleting file: {}", file.target.display());
+        delete(project_dir, &target)?;
+    }
+
+    Ok(())
+}
+
+/// Saves the mod config and the manifest
+fn save(modconfig: &ModConfig, config: &Path, manifest: &Manifest) -> Result<()> {
+    let path = crate::WORKDIR.join(config);
+    if crate::utils::dry_run() {
+        planned!("Write mod config: {}", path.display());
+        planned!("  includes = {:?}", modconfig.includes());
+        planned!("  modules = {:?}", modconfig.modules());
+        planned!("Write manifest");
+        return Ok(());
+    }
+
+    modconfig.save(path)?;
+    manifest.save()?;
+    Ok(())
+}
+
+/// Overwrites the file
+fn write(target: &Path, content: &[u8]) -> Result<()> {
+    if crate::utils::dry_run() {
+        planned!("Replace file: {}", target.display());
+        return Ok(());
+    }
+    std::fs::write(target, content)?;
+    Ok(())
+}
+
+/// Deletes the file and its empty parent directories
+fn delete(project_dir: &Path, target: &Path) -> Result<()> {
+    if crate::utils::dry_run() {
+        planned!("Delete file: {}", target.display());
+        return Ok(());
+    }
+    std::fs::remove_file(target)?;
+    remove_empty_parents(project_dir, target);
+    Ok(())
+}
+
+/// Removes the empty parent directories up to the project directory
+fn remove_empty_parents(project_dir: &Path, file: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if current == project_dir || std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Project with a module installed, returns the module and project directories
+    fn setup(name: &str, files: &[(&str, &str)]) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        let module_dir = dir.join("module");
+        let project_dir = dir.join("project");
+        fs::create_dir_all(module_dir.join("Config")).unwrap();
+        fs::create_dir_all(&project_dir).unwrap();
+
+        fs::write(module_dir.join("module.toml"), "name = 'test'").unwrap();
+        for (file, content) in files {
+            fs::write(module_dir.join("Config").join(file), content).unwrap();
+        }
+        fs::write(
+            project_dir.join("cvmod.toml"),
+            "project = 'Test'\npakname = 'Z_Test_P'\nincludes = []\nmodules = ['test']\n",
+        )
+        .unwrap();
+
+        let module = Module::load(module_dir.join("module.toml")).unwrap();
+        module.install(&project_dir, "Test").unwrap();
+        let mut manifest = Manifest::load(&project_dir).unwrap();
+        manifest.record(&module, "Test").unwrap();
+        manifest.save().unwrap();
+
+        (module_dir, project_dir)
+    }
+
+    fn upgrade(module_dir: &Path, project_dir: &Path) -> (UpgradeReport, Manifest) {
+        let module = Module::load(module_dir.join("module.toml")).unwrap();
+        let modconfig = ModConfig::load(project_dir.join("cvmod.toml")).unwrap();
+        let mut manifest = Manifest::load(project_dir).unwrap();
+        let mut report = UpgradeReport::default();
+
+        let upgrade = Upgrade { side_by_side: true };
+        upgrade
+            .upgrade_module(&module, &modconfig, &mut manifest, &mut report)
+            .unwrap();
+        (report, manifest)
+    }
+
+    #[test]
+    fn test_upgrade_unmodified() {
+        let (module_dir, project_dir) =
+            setup("cvmodtool_test_upgrade_unmodified", &[("A.ini", "a1")]);
+        fs::write(module_dir.join("Config/A.ini"), "a2").unwrap();
+
+        let (report, manifest) = upgrade(&module_dir, &project_dir);
+        assert_eq!(report.updated, 1);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(
+            fs::read_to_string(project_dir.join("Config/A.ini")).unwrap(),
+            "a2"
+        );
+        assert_eq!(
+            manifest.hash("test", Path::new("Config/A.ini")),
+            Some(&content_hash(b"a2"))
+        );
+
+        fs::remove_dir_all(project_dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_modified() {
+        let (module_dir, project_dir) =
+            setup("cvmodtool_test_upgrade_modified", &[("A.ini", "a1")]);
+        fs::write(module_dir.join("Config/A.ini"), "a2").unwrap();
+        fs::write(project_dir.join("Config/A.ini"), "local").unwrap();
+
+        let (report, manifest) = upgrade(&module_dir, &project_dir);
+        assert_eq!(report.updated, 0);
+        assert_eq!(report.conflicts, [PathBuf::from("Config/A.ini")]);
+        assert_eq!(
+            fs::read_to_string(project_dir.join("Config/A.ini")).unwrap(),
+            "local"
+        );
+        assert_eq!(
+            fs::read_to_string(project_dir.join("Config/A.ini.new")).unwrap(),
+            "a2"
+        );
+        // Still the installed version, so the conflict is reported again on the next upgrade
+        assert_eq!(
+            manifest.hash("test", Path::new("Config/A.ini")),
+            Some(&content_hash(b"a1"))
+        );
+
+        fs::remove_dir_all(project_dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_removed() {
+        let files = [("A.ini", "a"), ("B.ini", "b"), ("C.ini", "c")];
+        let (module_dir, project_dir) = setup("cvmodtool_test_upgrade_removed", &files);
+        fs::remove_file(module_dir.join("Config/A.ini")).unwrap();
+        fs::remove_file(module_dir.join("Config/B.ini")).unwrap();
+        fs::write(project_dir.join("Config/B.ini"), "local").unwrap();
+
+        let (report, manifest) = upgrade(&module_dir, &project_dir);
+        assert_eq!(report.deleted, 1);
+        assert!(!project_dir.join("Config/A.ini").exists());
+        assert_eq!(
+            fs::read_to_string(project_dir.join("Config/B.ini")).unwrap(),
+            "local"
+        );
+        assert_eq!(manifest.files("test").unwrap().len(), 1);
+
+        fs::remove_dir_all(project_dir.parent().unwrap()).unwrap();
+    }
+}
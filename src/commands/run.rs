@@ -0,0 +1,128 @@
+use super::build::Build;
+use super::install::Install;
+use super::package::Package;
+use anyhow::{Error, Result};
+use clap::error::ErrorKind;
+use clap::{Parser, ValueEnum};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Build, package and install the mod project in one go
+#[derive(Parser)]
+pub struct Run {
+    /// Mod configuration file to use
+    #[arg(short, long, default_value = "cvmod.toml")]
+    config: PathBuf,
+
+    /// First stage to run
+    #[arg(long, value_enum, default_value_t = Stage::Build)]
+    from: Stage,
+
+    /// Last stage to run
+    #[arg(long, value_enum, default_value_t = Stage::Install)]
+    to: Stage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Stage {
+    Build,
+    Package,
+    Install,
+}
+
+impl Stage {
+    const ALL: [Stage; 3] = [Stage::Build, Stage::Package, Stage::Install];
+
+    /// Exit code matching the stage's own command
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Build => -2,
+            Self::Package => -3,
+            Self::Install => -4,
+        }
+    }
+
+    /// Error message matching the stage's own command
+    pub fn failure_message(self) -> &'static str {
+        match self {
+            Self::Build => "Failed to build the project",
+            Self::Package => "Failed to package the project",
+            Self::Install => "Failed to install the package",
+        }
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Build => write!(f, "Build"),
+            Self::Package => write!(f, "Package"),
+            Self::Install => write!(f, "Install"),
+        }
+    }
+}
+
+impl Run {
+    /// Mod configuration file relative path
+    pub fn config(&self) -> &PathBuf {
+        &self.config
+    }
+
+    /// Checks the stage range (a usage error, not a failure of a stage)
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if self.from > self.to {
+            return Err(clap::Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!("--from ({}) is after --to ({})\n", self.from, self.to),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Execute command. On failure returns the stage that failed.
+    pub fn execute(&self) -> Result<(), (Stage, Error)> {
+        let stages = Stage::ALL
+            .into_iter()
+            .filter(|s| (self.from..=self.to).contains(s));
+
+        let mut timings = vec![];
+        for stage in stages {
+            let start = Instant::now();
+            let result = self.run_stage(stage);
+            timings.push((stage, start.elapsed(), result.is_ok()));
+
+            if let Err(err) = result {
+                print_summary(&timings);
+                return Err((stage, err));
+            }
+        }
+
+        print_summary(&timings);
+        Ok(())
+    }
+
+    fn run_stage(&self, stage: Stage) -> Result<()> {
+        let config = self.config().to_owned();
+        match stage {
+            Stage::Build => Build::new(config).execute(),
+            Stage::Package => Ok(Package::new(config).execute()?),
+            Stage::Install => Install::new(config).execute(),
+        }
+    }
+}
+
+fn print_summary(timings: &[(Stage, Duration, bool)]) {
+    important!("Summary:");
+    let mut total = Duration::ZERO;
+    for (stage, duration, success) in timings {
+        let status = if *success { "OK" } else { "FAILED" };
+        info!(
+            "  {:<8} {:>8.2}s  {status}",
+            stage.to_string(),
+            duration.as_secs_f64()
+        );
+        total += *duration;
+    }
+    info!("  {:<8} {:>8.2}s", "Total", total.as_secs_f64());
+}
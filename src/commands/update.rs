@@ -5,7 +5,6 @@ use semver::Version;
 use std::path::{Path, PathBuf};
 
 pub const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
-const VERSION_FILE: &str = "version";
 
 /// Update the tool and its resources
 #[derive(Parser)]
@@ -29,6 +28,10 @@ impl Update {
 
     /// Execute command
     pub fn execute(&self) -> Result<()> {
+        if crate::utils::dry_run() {
+            return Err(anyhow!("--dry-run is not supported by the update command!"));
+        }
+
         let mut executable = self.executable;
         let mut resources = self.resources;
 
@@ -89,7 +92,7 @@ fn update_resources() -> Result<()> {
     };
 
     let resources_dir = crate::resources::dir();
-    let version_file = resources_dir.join(VERSION_FILE);
+    let version_file = crate::resources::version_file();
 
     let current = read_version(&version_file)?;
     let latest = updater.version()?;
@@ -179,6 +182,7 @@ fn extract(asset: &Path, target: &Path) -> Result<()> {
 
         #[cfg(unix)]
         {
+            use std::fs;
             use std::os::unix::fs::PermissionsExt;
 
             if let Some(mode) = file.unix_mode() {
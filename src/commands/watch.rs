@@ -0,0 +1,139 @@
+use super::install::Install;
+use super::package::Package;
+use crate::config::ModConfig;
+use crate::utils::native_path;
+use anyhow::Result;
+use clap::Parser;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use walkdir::WalkDir;
+
+/// Time between two checks for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Package and install the mod project every time its cooked content changes
+#[derive(Parser)]
+pub struct Watch {
+    /// Mod configuration file to use
+    #[arg(short, long, default_value = "cvmod.toml")]
+    config: PathBuf,
+
+    /// Milliseconds without changes to wait for before packaging (the cook writes many files)
+    #[arg(long, default_value_t = 2000)]
+    debounce: u64,
+}
+
+/// Size and modification time of the watched files
+type Snapshot = BTreeMap<PathBuf, (u64, Option<SystemTime>)>;
+
+impl Watch {
+    /// Execute command. Runs until it's stopped (Ctrl+C), failed cycles don't stop it.
+    pub fn execute(&self) -> Result<()> {
+        verbose!("Loading mod config...");
+        let modconfig = ModConfig::load(&self.config)?;
+
+        // Cooked content and the raw content copied into the paks (ContentPreCooked by default)
+        let mut dirs = vec![modconfig.cooked_content_dir()];
+        for target in modconfig.targets() {
+            for copy in target.copies() {
+                let source = modconfig.wd().join(native_path(copy.source()));
+                if !dirs.contains(&source) {
+                    dirs.push(source);
+                }
+            }
+        }
+
+        important!("Watching for changes... (Ctrl+C to stop)");
+        for dir in &dirs {
+            info!("  {}", dir.display());
+        }
+
+        let debounce = Duration::from_millis(self.debounce);
+        let mut current = snapshot(&dirs);
+        let mut cycle = 0;
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let mut next = snapshot(&dirs);
+            if next == current {
+                continue;
+            }
+
+            // Wait for the cook to finish writing
+            loop {
+                std::thread::sleep(debounce);
+                let settled = snapshot(&dirs);
+                if settled == next {
+                    break;
+                }
+                next = settled;
+            }
+
+            let changes = count_changes(&current, &next);
+            current = next;
+            cycle += 1;
+
+            let start = Instant::now();
+            let result = self.run_cycle();
+            let elapsed = start.elapsed().as_secs_f64();
+
+            match result {
+                Ok(()) => important!(
+                    "[#{cycle}] {changes} files changed, packaged and installed in {elapsed:.2}s"
+                ),
+                Err(err) => {
+                    error!("[#{cycle}] {changes} files changed, failed after {elapsed:.2}s: {err}")
+                }
+            }
+            important!("Watching for changes...");
+        }
+    }
+
+    fn run_cycle(&self) -> Result<()> {
+        Package::new(self.config.to_owned()).execute()?;
+        Install::new(self.config.to_owned()).execute()
+    }
+}
+
+/// Files of the directories with their size and modification time
+fn snapshot(dirs: &[PathBuf]) -> Snapshot {
+    let mut files = Snapshot::new();
+
+    for dir in dirs {
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if let Ok(metadata) = entry.metadata() {
+                let state = (metadata.len(), metadata.modified().ok());
+                files.insert(entry.into_path(), state);
+            }
+        }
+    }
+
+    files
+}
+
+/// Number of files added, removed or modified
+fn count_changes(old: &Snapshot, new: &Snapshot) -> usize {
+    let changed = new.iter().filter(|(f, s)| old.get(*f) != Some(s)).count();
+    let removed = old.keys().filter(|f| !new.contains_key(*f)).count();
+    changed + removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_changes() {
+        let file = |name: &str, size| (PathBuf::from(name), (size, None));
+        let old = Snapshot::from([file("a", 1), file("b", 2), file("c", 3)]);
+        let new = Snapshot::from([file("a", 1), file("b", 5), file("d", 4)]);
+
+        assert_eq!(count_changes(&old, &old), 0);
+        // b modified, c removed, d added
+        assert_eq!(count_changes(&old, &new), 3);
+    }
+}
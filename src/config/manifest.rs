@@ -0,0 +1,111 @@
+use crate::resources::modules::Module;
+use crate::utils::{content_hash, path_key};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const FILE_NAME: &str = "cvmod.manifest.toml";
+
+/// Files installed into the project by each module with their hashes
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Module name -> (project relative file path -> SHA1 hex)
+    #[serde(default)]
+    modules: BTreeMap<String, BTreeMap<String, String>>,
+    /// Manifest file path
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Manifest {
+    /// Load from the project directory. Returns an empty manifest if it doesn't exist.
+    pub fn load<P: AsRef<Path>>(project_dir: P) -> Result<Self, ManifestError> {
+        let path = project_dir.as_ref().join(FILE_NAME);
+
+        let mut manifest: Self = if path.is_file() {
+            let content = std::fs::read_to_string(&path).map_err(ManifestError::Read)?;
+            toml::from_str(&content)?
+        } else {
+            Self::default()
+        };
+
+        manifest.path = path;
+        Ok(manifest)
+    }
+
+    /// Save to disk
+    pub fn save(&self) -> Result<(), ManifestError> {
+        let contents = toml::to_string_pretty(self)?;
+        std::fs::write(&self.path, contents).map_err(ManifestError::Write)
+    }
+
+    /// Records the hashes of the files the module installs. (Merged files are skipped)
+    pub fn record(&mut self, module: &Module, project: &str) -> Result<(), ManifestError> {
+        let mut files = BTreeMap::new();
+        for file in module.files(project).map_err(ManifestError::Module)? {
+            if file.is_merged() {
+                continue;
+            }
+
+            let content = file.content(project).map_err(ManifestError::ModuleFile)?;
+            files.insert(path_key(&file.target), content_hash(&content));
+        }
+
+        self.modules.insert(module.name().to_string(), files);
+        Ok(())
+    }
+
+    /// Recorded files of the module
+    pub fn files(&self, module: &str) -> Option<&BTreeMap<String, String>> {
+        self.modules.get(module)
+    }
+
+    /// Recorded hash of a file installed by the module
+    pub fn hash(&self, module: &str, file: &Path) -> Option<&String> {
+        self.modules
+            .get(module)
+            .and_then(|f| f.get(&path_key(file)))
+    }
+
+    /// Updates the recorded hash of a file
+    pub fn set_hash(&mut self, module: &str, file: &Path, hash: String) {
+        self.modules
+            .entry(module.to_string())
+            .or_default()
+            .insert(path_key(file), hash);
+    }
+
+    /// Removes the record of a file
+    pub fn remove_file(&mut self, module: &str, file: &Path) {
+        if let Some(files) = self.modules.get_mut(module) {
+            files.remove(&path_key(file));
+        }
+    }
+
+    /// Removes the records of the module
+    pub fn remove_module(&mut self, module: &str) {
+        self.modules.remove(module);
+    }
+
+    /// Checks if the paths are the same regardless of the separator
+    pub fn same_path(a: &Path, b: &Path) -> bool {
+        path_key(a) == path_key(b)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Failed to read manifest. ({0})")]
+    Read(#[source] std::io::Error),
+    #[error("Failed to parse manifest. ({0})")]
+    Parse(#[from] toml::de::Error),
+    #[error("Failed to serialize manifest. ({0})")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Failed to save manifest. ({0})")]
+    Write(#[source] std::io::Error),
+    #[error("Failed to get module files. ({0})")]
+    Module(#[source] anyhow::Error),
+    #[error("Failed to read module file. ({0})")]
+    ModuleFile(#[source] std::io::Error),
+}
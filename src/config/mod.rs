@@ -1,5 +1,10 @@
+mod manifest;
 mod modconfig;
+mod runner;
+mod staging;
 mod toolconfig;
 
-pub use modconfig::{ModConfig, ModConfigError};
-pub use toolconfig::{ToolConfig, ToolConfigError};
+pub use manifest::Manifest;
+pub use modconfig::{BuildOptions, CopyEntry, ModConfig, ModConfigError, PakTarget};
+pub use staging::{StagedFile, Staging, StagingError};
+pub use toolconfig::{ToolConfig, ToolConfigError, ValueOrigin, TOOL_CONFIG};
@@ -1,6 +1,8 @@
+use crate::utils::native_path;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
+use toml_edit::DocumentMut;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModConfig {
@@ -10,43 +12,240 @@ pub struct ModConfig {
     pakname: String,
     /// Files/folders to include in the package
     includes: Includes,
+    /// Cooked files/folders to exclude from the package
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    excludes: Vec<PathBuf>,
+    /// Compress the .pak file
+    #[serde(
+        default = "default_compress",
+        skip_serializing_if = "is_default_compress"
+    )]
+    compress: bool,
+    /// Additional .pak files to create from the project
+    #[serde(default, rename = "pak", skip_serializing_if = "Vec::is_empty")]
+    paks: Vec<PakTarget>,
+    /// Options of the build command
+    #[serde(default, skip_serializing_if = "BuildOptions::is_default")]
+    build: BuildOptions,
+    /// Modules installed into the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    modules: Vec<String>,
     /// Relative path/name of the directory to do packaging in
-    #[serde(skip_serializing, default = "default_packagedir")]
+    #[serde(
+        default = "default_packagedir",
+        skip_serializing_if = "is_default_packagedir"
+    )]
     packagedir: PathBuf,
     /// Config working directory
     #[serde(skip)]
     wd: PathBuf,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 enum IncludesBC {
     Simple(Vec<PathBuf>),
     Detailed {
         #[serde(default, alias = "cooked")]
         cook: Vec<PathBuf>,
-        //#[serde(default, alias = "raw")]
-        //copy: Vec<PathBuf>,
+        #[serde(default = "default_copy", alias = "raw")]
+        copy: Vec<CopyEntry>,
     },
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-#[serde(from = "IncludesBC")]
-pub struct Includes(Vec<PathBuf>);
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "IncludesBC", into = "IncludesBC")]
+pub struct Includes {
+    /// Cooked content files/folders (relative to the Content directory)
+    cook: Vec<PathBuf>,
+    /// Raw (uncooked) files/folders copied as is
+    copy: Vec<CopyEntry>,
+}
+
+impl Default for Includes {
+    fn default() -> Self {
+        Self {
+            cook: vec![],
+            copy: default_copy(),
+        }
+    }
+}
 
 impl From<IncludesBC> for Includes {
     fn from(value: IncludesBC) -> Self {
         match value {
-            IncludesBC::Simple(includes) => Self(includes),
-            IncludesBC::Detailed { cook } => Self(cook),
+            IncludesBC::Simple(cook) => Self {
+                cook,
+                copy: default_copy(),
+            },
+            IncludesBC::Detailed { cook, copy } => Self { cook, copy },
         }
     }
 }
 
+impl From<Includes> for IncludesBC {
+    fn from(value: Includes) -> Self {
+        if value.copy == default_copy() {
+            Self::Simple(value.cook)
+        } else {
+            Self::Detailed {
+                cook: value.cook,
+                copy: value.copy,
+            }
+        }
+    }
+}
+
+/// Raw file/folder to copy into the package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyEntry {
+    /// Project relative path of the file/folder
+    #[serde(alias = "from")]
+    source: PathBuf,
+    /// Destination inside the pak. For folders it is the destination folder,
+    /// for files it is the destination file. (default: CodeVein/Content)
+    #[serde(default, alias = "to", skip_serializing_if = "Option::is_none")]
+    target: Option<PathBuf>,
+}
+
+impl CopyEntry {
+    /// Project relative path of the file/folder
+    pub fn source(&self) -> &PathBuf {
+        &self.source
+    }
+
+    /// Destination inside the pak (relative to the pak root)
+    pub fn target(&self) -> Option<&PathBuf> {
+        self.target.as_ref()
+    }
+
+    /// Checks if it's the default entry (ContentPreCooked, which projects may not have)
+    pub fn is_default(&self) -> bool {
+        default_copy().contains(self)
+    }
+}
+
+/// A .pak file created from the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PakTarget {
+    /// Name of the .pak file
+    name: String,
+    /// Cooked content files/folders (relative to the Content directory)
+    #[serde(default)]
+    includes: Vec<PathBuf>,
+    /// Cooked content files/folders to leave out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    excludes: Vec<PathBuf>,
+    /// Raw (uncooked) files/folders copied as is
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    copy: Vec<CopyEntry>,
+    /// Compress the .pak file
+    #[serde(
+        default = "default_compress",
+        skip_serializing_if = "is_default_compress"
+    )]
+    compress: bool,
+}
+
+impl PakTarget {
+    /// Name of the .pak file
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Cooked content includes
+    pub fn includes(&self) -> &Vec<PathBuf> {
+        &self.includes
+    }
+
+    /// Cooked content excludes
+    pub fn excludes(&self) -> &Vec<PathBuf> {
+        &self.excludes
+    }
+
+    /// Raw files/folders to copy into the package
+    pub fn copies(&self) -> &Vec<CopyEntry> {
+        &self.copy
+    }
+
+    /// Compress the .pak file
+    pub fn compress(&self) -> bool {
+        self.compress
+    }
+}
+
+/// Options of the build command (UAT arguments)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildOptions {
+    /// Maps to cook (e.g. /Game/Maps/work). Every map is cooked if empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    maps: Vec<String>,
+    /// Only cook the content changed since the last cook
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    iterative: bool,
+    /// Default UAT arguments to leave out (e.g. -SkipCookingEditorContent or -targetplatform)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    remove_args: Vec<String>,
+    /// Additional UAT arguments
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra_args: Vec<String>,
+}
+
+impl BuildOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Maps to cook
+    pub fn maps(&self) -> &Vec<String> {
+        &self.maps
+    }
+
+    /// Only cook the content changed since the last cook
+    pub fn iterative(&self) -> bool {
+        self.iterative
+    }
+
+    /// Checks if the default UAT argument is removed. Arguments with a value are
+    /// matched by their name as well. (-targetplatform removes -targetplatform=Win64)
+    pub fn removes(&self, arg: &str) -> bool {
+        let name = arg.split_once('=').map_or(arg, |(name, _)| name);
+        self.remove_args
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(arg) || r.eq_ignore_ascii_case(name))
+    }
+
+    /// Additional UAT arguments
+    pub fn extra_args(&self) -> &Vec<String> {
+        &self.extra_args
+    }
+}
+
+/// Copy the ContentPreCooked folder by default
+fn default_copy() -> Vec<CopyEntry> {
+    vec![CopyEntry {
+        source: PathBuf::from("ContentPreCooked"),
+        target: None,
+    }]
+}
+
+fn default_compress() -> bool {
+    true
+}
+
+fn is_default_compress(compress: &bool) -> bool {
+    *compress == default_compress()
+}
+
 fn default_packagedir() -> PathBuf {
     PathBuf::from("Package")
 }
 
+fn is_default_packagedir(packagedir: &PathBuf) -> bool {
+    *packagedir == default_packagedir()
+}
+
 impl ModConfig {
     pub fn new<P: AsRef<Path>>(name: &str, wd: P) -> Self {
         Self {
@@ -54,6 +253,11 @@ impl ModConfig {
             project: name.to_string(),
             packagedir: default_packagedir(),
             includes: Includes::default(),
+            excludes: vec![],
+            compress: default_compress(),
+            paks: vec![],
+            build: BuildOptions::default(),
+            modules: vec![],
             wd: wd.as_ref().to_path_buf(),
         }
     }
@@ -68,6 +272,15 @@ impl ModConfig {
 
         let content = std::fs::read_to_string(&path).map_err(ModConfigError::Read)?;
         let mut config: Self = toml::from_str(&content).map_err(ModConfigError::Parse)?;
+
+        let mut names = vec![config.pakname()];
+        for pak in &config.paks {
+            if names.iter().any(|n| n.eq_ignore_ascii_case(pak.name())) {
+                return Err(ModConfigError::DuplicateTarget(pak.name().to_owned()));
+            }
+            names.push(pak.name());
+        }
+
         config.wd = {
             path.pop();
             path
@@ -76,24 +289,87 @@ impl ModConfig {
         Ok(config)
     }
 
-    /// Save to disk
+    /// Save to disk. An existing file is only changed where the config differs, so its
+    /// comments and the keys unknown to this version are kept.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ModConfigError> {
-        let contents = toml::to_string_pretty(self)?;
+        let path = path.as_ref();
+        let contents = match std::fs::read_to_string(path) {
+            Ok(existing) => self.update(&existing)?,
+            Err(_) => toml::to_string_pretty(self)?,
+        };
         std::fs::write(path, contents).map_err(ModConfigError::Write)
     }
 
+    /// Replaces the changed top-level keys of the existing file content.
+    /// Unparsable content is replaced entirely.
+    fn update(&self, existing: &str) -> Result<String, ModConfigError> {
+        let contents = toml::to_string_pretty(self)?;
+        let (Ok(mut document), Ok(new), Ok(old)) = (
+            existing.parse::<DocumentMut>(),
+            contents.parse::<DocumentMut>(),
+            toml::from_str::<ModConfig>(existing),
+        ) else {
+            return Ok(contents);
+        };
+
+        // Compare what the values mean, not how they were written
+        let old = toml::Table::try_from(old)?;
+        let current = toml::Table::try_from(self)?;
+        for key in old.keys().chain(current.keys()) {
+            if old.get(key) == current.get(key) {
+                continue;
+            }
+            match new.get(key) {
+                Some(item) => document.insert(key, item.clone()),
+                None => document.remove(key),
+            };
+        }
+
+        Ok(document.to_string())
+    }
+
     /// Pak file name
     pub fn pakname(&self) -> &String {
         &self.pakname
     }
 
-    /// Pak file absolute path
-    pub fn pakfile(&self) -> PathBuf {
-        let mut path = self.wd().join(self.packagedir());
-        path.push(format!("{}.pak", self.pakname()));
+    /// Pak file absolute path of the target
+    pub fn pakfile(&self, target: &PakTarget) -> PathBuf {
+        let mut path = self.wd().join(native_path(self.packagedir()));
+        path.push(format!("{}.pak", target.name()));
         path
     }
 
+    /// Pak targets of the project. The main pak is always the first one.
+    pub fn targets(&self) -> Vec<PakTarget> {
+        let main = PakTarget {
+            name: self.pakname.clone(),
+            includes: self.includes.cook.clone(),
+            excludes: self.excludes.clone(),
+            copy: self.includes.copy.clone(),
+            compress: self.compress,
+        };
+
+        std::iter::once(main).chain(self.paks.clone()).collect()
+    }
+
+    /// Selected pak target or all of them if none is selected
+    pub fn select_targets(&self, target: Option<&str>) -> Result<Vec<PakTarget>, ModConfigError> {
+        let mut targets = self.targets();
+        if let Some(name) = target {
+            targets.retain(|t| t.name().eq_ignore_ascii_case(name));
+            if targets.is_empty() {
+                return Err(ModConfigError::TargetNotFound(name.to_owned()));
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Options of the build command
+    pub fn build(&self) -> &BuildOptions {
+        &self.build
+    }
+
     /// Project name
     pub fn project(&self) -> &String {
         &self.project
@@ -104,19 +380,35 @@ impl ModConfig {
         self.wd().join(format!("{}.uproject", &self.project()))
     }
 
+    /// Cooked content directory absolute path
+    pub fn cooked_content_dir(&self) -> PathBuf {
+        let relative = format!("Saved/Cooked/WindowsNoEditor/{}/Content", self.project());
+        self.wd().join(native_path(relative))
+    }
+
     /// Package directory relative path
     pub fn packagedir(&self) -> &PathBuf {
         &self.packagedir
     }
 
-    /// Package includes
+    /// Package includes (cooked content)
     pub fn includes(&self) -> &Vec<PathBuf> {
-        &self.includes.0
+        &self.includes.cook
     }
 
-    /// Set package includes
+    /// Set package includes (cooked content)
     pub fn set_includes(&mut self, includes: Vec<PathBuf>) {
-        self.includes = Includes(includes);
+        self.includes.cook = includes;
+    }
+
+    /// Modules installed into the project
+    pub fn modules(&self) -> &Vec<String> {
+        &self.modules
+    }
+
+    /// Set modules installed into the project
+    pub fn set_modules(&mut self, modules: Vec<String>) {
+        self.modules = modules;
     }
 
     /// Mod working directory
@@ -129,6 +421,10 @@ impl ModConfig {
 pub enum ModConfigError {
     #[error("Mod config file ({0}) not found!")]
     NotFound(String),
+    #[error("Pak target \"{0}\" is defined more than once!")]
+    DuplicateTarget(String),
+    #[error("Pak target \"{0}\" was not found in the mod config!")]
+    TargetNotFound(String),
     #[error("Failed to read mod config. ({0})")]
     Read(#[source] std::io::Error),
     #[error("Failed to parse mod config. ({0})")]
@@ -138,3 +434,127 @@ pub enum ModConfigError {
     #[error("Failed to save mod config. ({0})")]
     Write(#[source] std::io::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_includes() {
+        let config: ModConfig =
+            toml::from_str("project = 'Test'\npakname = 'Z_Test_P'\nincludes = ['Maps']").unwrap();
+        assert_eq!(config.includes(), &[PathBuf::from("Maps")]);
+        assert_eq!(config.targets()[0].copies(), &default_copy());
+        assert!(config.targets()[0].copies()[0].is_default());
+
+        let config: ModConfig = toml::from_str(
+            r#"
+            project = 'Test'
+            pakname = 'Z_Test_P'
+            [includes]
+            cook = ['Maps']
+            copy = [{ source = 'Raw/Test.ini', target = 'CodeVein/Config/Test.ini' }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.targets()[0].copies().len(), 1);
+        assert!(!config.targets()[0].copies()[0].is_default());
+        assert_eq!(
+            config.targets()[0].copies()[0].target(),
+            Some(&PathBuf::from("CodeVein/Config/Test.ini"))
+        );
+
+        // Detailed form survives a save
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: ModConfig = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.targets()[0].copies(), config.targets()[0].copies());
+        assert_eq!(reloaded.includes(), config.includes());
+    }
+
+    #[test]
+    fn test_targets() {
+        let config: ModConfig = toml::from_str(
+            r#"
+            project = 'Test'
+            pakname = 'Z_Test_P'
+            includes = ['Maps']
+            [[pak]]
+            name = 'Z_TestHard_P'
+            includes = ['Enemies']
+            compress = false
+            "#,
+        )
+        .unwrap();
+
+        let targets = config.targets();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].name(), "Z_Test_P");
+        assert!(targets[0].compress());
+        assert_eq!(targets[1].includes(), &[PathBuf::from("Enemies")]);
+        assert!(targets[1].copies().is_empty());
+        assert!(!targets[1].compress());
+
+        let selected = config.select_targets(Some("z_testhard_p")).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name(), "Z_TestHard_P");
+        assert!(config.select_targets(Some("Missing")).is_err());
+    }
+
+    #[test]
+    fn test_build_options() {
+        let config: ModConfig =
+            toml::from_str("project = 'Test'\npakname = 'Z_Test_P'\nincludes = ['Maps']").unwrap();
+        assert_eq!(config.build(), &BuildOptions::default());
+        assert!(!toml::to_string_pretty(&config).unwrap().contains("[build]"));
+
+        let config: ModConfig = toml::from_str(
+            r#"
+            project = 'Test'
+            pakname = 'Z_Test_P'
+            includes = ['Maps']
+            [build]
+            maps = ['/Game/Maps/work']
+            iterative = true
+            remove_args = ['-targetplatform', '-SkipCookingEditorContent']
+            extra_args = ['-compressed']
+            "#,
+        )
+        .unwrap();
+
+        let build = config.build();
+        assert_eq!(build.maps(), &["/Game/Maps/work"]);
+        assert!(build.iterative());
+        assert!(build.removes("-targetplatform=Win64"));
+        assert!(build.removes("-skipcookingeditorcontent"));
+        assert!(!build.removes("-cook"));
+        assert_eq!(build.extra_args(), &["-compressed"]);
+    }
+
+    #[test]
+    fn test_update() {
+        let existing = "# Test mod
+project = 'Test' # Unreal project
+pakname = 'Z_Test_P'
+includes = ['Maps'] # Cooked folders
+compress = true
+unknown = 'kept'
+modules = ['base']
+";
+        let mut config: ModConfig = toml::from_str(existing).unwrap();
+        config.set_includes(vec![PathBuf::from("Maps"), PathBuf::from("Ladder")]);
+        config.set_modules(vec!["base".to_string(), "ladder".to_string()]);
+
+        let updated = config.update(existing).unwrap();
+        assert!(updated.starts_with("# Test mod\nproject = 'Test' # Unreal project\n"));
+        assert!(updated.contains("compress = true\nunknown = 'kept'\n"));
+        let reloaded: ModConfig = toml::from_str(&updated).unwrap();
+        assert_eq!(reloaded.includes(), config.includes());
+        assert_eq!(reloaded.modules(), config.modules());
+
+        // Values back to the default are removed
+        config.set_modules(vec![]);
+        let updated = config.update(existing).unwrap();
+        assert!(!updated.contains("modules"));
+        assert!(updated.contains("unknown = 'kept'"));
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Launches the Windows engine tools through another program (e.g. Wine or Proton)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Runner {
+    /// Program (and its arguments) to launch the tools with. e.g. ['wine']
+    command: Vec<String>,
+    /// Wine prefix to use (sets WINEPREFIX)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prefix: Option<PathBuf>,
+    /// Additional environment variables
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// Translate the paths passed to the tools into Wine (Z:\) paths
+    #[serde(default = "default_translate")]
+    translate: bool,
+}
+
+fn default_translate() -> bool {
+    true
+}
+
+impl Runner {
+    /// Command launching the program through the runner
+    pub fn command(&self, program: &Path) -> Command {
+        let mut command = match self.command.split_first() {
+            Some((runner, args)) => {
+                let mut command = Command::new(runner);
+                command.args(args).arg(program);
+                command
+            }
+            None => Command::new(program),
+        };
+
+        if let Some(prefix) = &self.prefix {
+            command.env("WINEPREFIX", prefix);
+        }
+        command.envs(&self.env);
+        command
+    }
+
+    /// Path as seen by the launched program
+    pub fn path(&self, path: &Path) -> String {
+        if !self.translate {
+            return path.display().to_string();
+        }
+
+        // Wine maps the root of the host file system to the Z: drive
+        let path = path.to_string_lossy();
+        match path.strip_prefix('/') {
+            Some(path) => format!("Z:\\{}", path.replace('/', "\\")),
+            None => path.replace('/', "\\"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let runner: Runner = toml::from_str("command = ['wine']").unwrap();
+        assert_eq!(
+            runner.path(Path::new("/home/user/Mods/Test/Test.uproject")),
+            "Z:\\home\\user\\Mods\\Test\\Test.uproject"
+        );
+        assert_eq!(runner.path(Path::new("Package/Test")), "Package\\Test");
+
+        let runner: Runner = toml::from_str("command = ['wine']\ntranslate = false").unwrap();
+        assert_eq!(runner.path(Path::new("/home/user")), "/home/user");
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use thiserror::Error;
+
+/// Files staged for a pak file. Used to only sync the changed files.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Staging {
+    /// Compression of the pak file created from the staged files.
+    /// Not set if the pak file wasn't created since the last change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    packed: Option<bool>,
+    /// Pak relative file path -> source file state
+    #[serde(default)]
+    files: BTreeMap<String, StagedFile>,
+    /// Staging manifest file path
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// State of the source file when it was staged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StagedFile {
+    size: u64,
+    /// Modification time in milliseconds since the Unix epoch
+    modified: u64,
+    /// SHA1 hex of the content
+    hash: String,
+}
+
+impl StagedFile {
+    pub fn new(metadata: &Metadata, hash: String) -> Self {
+        Self {
+            size: metadata.len(),
+            modified: modified(metadata),
+            hash,
+        }
+    }
+}
+
+impl Staging {
+    /// Empty staging manifest
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Load from disk. Returns an empty manifest if it doesn't exist.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, StagingError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Ok(Self::new(path));
+        }
+
+        let content = std::fs::read_to_string(path).map_err(StagingError::Read)?;
+        let mut staging: Self = toml::from_str(&content)?;
+        staging.path = path.to_path_buf();
+        Ok(staging)
+    }
+
+    /// Save to disk
+    pub fn save(&self) -> Result<(), StagingError> {
+        let contents = toml::to_string_pretty(self)?;
+        std::fs::write(&self.path, contents).map_err(StagingError::Write)
+    }
+
+    /// Checks if no files were staged
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Staged files (pak relative paths)
+    pub fn files(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Checks if the source file's size and modification time are the same as when it was staged
+    pub fn is_unchanged(&self, file: &str, metadata: &Metadata) -> bool {
+        self.files
+            .get(file)
+            .is_some_and(|f| f.size == metadata.len() && f.modified == modified(metadata))
+    }
+
+    /// Recorded hash of the staged file
+    pub fn hash(&self, file: &str) -> Option<&String> {
+        self.files.get(file).map(|f| &f.hash)
+    }
+
+    /// Records the staged file
+    pub fn insert(&mut self, file: String, staged: StagedFile) {
+        self.files.insert(file, staged);
+    }
+
+    /// Removes the record of the staged file
+    pub fn remove(&mut self, file: &str) {
+        self.files.remove(file);
+    }
+
+    /// Checks if the pak file was created with the compression since the last change
+    pub fn is_packed(&self, compress: bool) -> bool {
+        self.packed == Some(compress)
+    }
+
+    /// Sets the compression the pak file was created with (None if it needs to be recreated)
+    pub fn set_packed(&mut self, packed: Option<bool>) {
+        self.packed = packed;
+    }
+}
+
+fn modified(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Error)]
+pub enum StagingError {
+    #[error("Failed to read staging manifest. ({0})")]
+    Read(#[source] std::io::Error),
+    #[error("Failed to parse staging manifest. ({0})")]
+    Parse(#[from] toml::de::Error),
+    #[error("Failed to serialize staging manifest. ({0})")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Failed to save staging manifest. ({0})")]
+    Write(#[source] std::io::Error),
+}
@@ -1,16 +1,96 @@
+use super::runner::Runner;
+use crate::engine::{self, EngineError};
 use crate::resources::profiles::Profiles;
+use crate::utils::native_path;
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use thiserror::Error;
 
 const FILE_NAME: &str = "config.toml";
 
+/// Environment variable selecting the config file
+const CONFIG_ENV: &str = "CVMODTOOL_CONFIG";
+/// Environment variable overriding the engine
+const ENGINE_ENV: &str = "CVMODTOOL_ENGINE";
+/// Environment variable overriding the mods folder
+const MODDIR_ENV: &str = "CVMODTOOL_MODDIR";
+
+/// Config file given with the --tool-config option
+pub static TOOL_CONFIG: OnceCell<PathBuf> = OnceCell::new();
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolConfig {
     engine: PathBuf,
     moddir: PathBuf,
+    /// Runs the engine tools through Wine/Proton
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    runner: Option<Runner>,
     #[serde(default)]
     profiles: Profiles,
+    /// Values overridden by the environment variables
+    #[serde(skip)]
+    overrides: Overrides,
+    /// Loaded only from the environment variables (there is no config file)
+    #[serde(skip)]
+    env_only: bool,
+}
+
+#[derive(Debug, Default)]
+struct Overrides {
+    engine: Option<PathBuf>,
+    moddir: Option<PathBuf>,
+}
+
+impl Overrides {
+    fn from_env() -> Self {
+        Self {
+            engine: std::env::var_os(ENGINE_ENV).map(PathBuf::from),
+            moddir: std::env::var_os(MODDIR_ENV).map(PathBuf::from),
+        }
+    }
+}
+
+/// Where the config file was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// --tool-config option
+    Argument,
+    /// CVMODTOOL_CONFIG environment variable
+    Environment,
+    /// Platform config directory of the user
+    UserDir,
+    /// Next to the executable
+    ExeDir,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Argument => write!(f, "--tool-config option"),
+            Self::Environment => write!(f, "{CONFIG_ENV} environment variable"),
+            Self::UserDir => write!(f, "user config directory"),
+            Self::ExeDir => write!(f, "executable directory"),
+        }
+    }
+}
+
+/// Where the effective value of a key came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueOrigin {
+    File(PathBuf),
+    Environment(&'static str),
+}
+
+impl Display for ValueOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Environment(var) => write!(f, "{var} environment variable"),
+        }
+    }
 }
 
 impl ToolConfig {
@@ -18,39 +98,133 @@ impl ToolConfig {
         Self {
             engine,
             moddir,
+            runner: None,
             profiles: Profiles::new(),
+            overrides: Overrides::default(),
+            env_only: false,
         }
     }
 
-    /// Checks if the config file exists
+    /// Checks if the config file exists (or every required value is set by the environment)
     pub fn check() -> bool {
-        config_path().is_file()
+        let overrides = Overrides::from_env();
+        config_path().0.is_file() || (overrides.engine.is_some() && overrides.moddir.is_some())
+    }
+
+    /// Path of the config file
+    pub fn path() -> PathBuf {
+        config_path().0
     }
 
-    /// Saves the config to file
+    /// Where the config file was found
+    pub fn source() -> ConfigSource {
+        config_path().1
+    }
+
+    /// Saves the config to file. (The environment overrides are not saved)
     pub fn save(&self) -> Result<(), ToolConfigError> {
+        // Its file values are empty, saving it would create a broken config
+        if self.env_only {
+            return Err(ToolConfigError::EnvOnly);
+        }
+
         let content = toml::to_string_pretty(self)?;
-        let path = config_path();
+        let path = config_path().0;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(ToolConfigError::Write)?;
+        }
         std::fs::write(path, content).map_err(ToolConfigError::Write)
     }
 
-    /// Loads the config from file
+    /// Loads the config from file and applies the environment overrides
     pub fn load() -> Result<Self, ToolConfigError> {
-        let path = config_path();
-        let content = std::fs::read_to_string(path).map_err(ToolConfigError::Read)?;
-        toml::from_str(&content).map_err(ToolConfigError::Parse)
+        let path = config_path().0;
+        let overrides = Overrides::from_env();
+
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content).map_err(ToolConfigError::Parse)?,
+            // Running only from the environment (e.g. CI)
+            Err(_) if overrides.engine.is_some() && overrides.moddir.is_some() => Self {
+                env_only: true,
+                ..Self::new(PathBuf::new(), PathBuf::new())
+            },
+            Err(err) => return Err(ToolConfigError::Read(err)),
+        };
+
+        config.overrides = overrides;
+        Ok(config)
     }
 
-    pub fn uat(&self) -> PathBuf {
-        self.engine.join("Engine\\Build\\BatchFiles\\RunUAT.bat")
+    /// RunUAT.bat of the engine. Fails if the engine is not a valid UE 4.18 install.
+    pub fn uat(&self) -> Result<PathBuf, ToolConfigError> {
+        engine::verify(self.engine())?;
+        Ok(native_path(self.engine().join(engine::UAT)))
     }
 
-    pub fn upak(&self) -> PathBuf {
-        self.engine.join("Engine\\Binaries\\Win64\\UnrealPak.exe")
+    /// UnrealPak.exe of the engine. Fails if the engine is not a valid UE 4.18 install.
+    pub fn upak(&self) -> Result<PathBuf, ToolConfigError> {
+        engine::verify(self.engine())?;
+        Ok(native_path(self.engine().join(engine::UNREALPAK)))
+    }
+
+    /// UnrealBuildTool.exe of the engine. Fails if the engine is not a valid UE 4.18 install
+    /// or UBT is missing from it. (Only needed for projects with C++ code)
+    pub fn ubt(&self) -> Result<PathBuf, ToolConfigError> {
+        engine::verify(self.engine())?;
+        let ubt = native_path(self.engine().join(engine::UBT));
+        if !ubt.is_file() {
+            return Err(EngineError::MissingTool(ubt).into());
+        }
+        Ok(ubt)
+    }
+
+    /// Command launching the engine tool (through the runner if it's set)
+    pub fn command(&self, program: &Path) -> Command {
+        match &self.runner {
+            Some(runner) => runner.command(program),
+            None => Command::new(program),
+        }
+    }
+
+    /// Path as seen by the engine tools
+    pub fn tool_path(&self, path: &Path) -> String {
+        match &self.runner {
+            Some(runner) => runner.path(path),
+            None => path.display().to_string(),
+        }
+    }
+
+    /// UE 4.18 install folder
+    pub fn engine(&self) -> &PathBuf {
+        self.overrides.engine.as_ref().unwrap_or(&self.engine)
+    }
+
+    /// Where the effective engine came from
+    pub fn engine_origin(&self) -> ValueOrigin {
+        match self.overrides.engine {
+            Some(_) => ValueOrigin::Environment(ENGINE_ENV),
+            None => ValueOrigin::File(Self::path()),
+        }
+    }
+
+    pub fn set_engine(&mut self, engine: PathBuf) {
+        self.engine = engine;
     }
 
     pub fn moddir(&self) -> &PathBuf {
-        &self.moddir
+        self.overrides.moddir.as_ref().unwrap_or(&self.moddir)
+    }
+
+    /// Where the effective mods folder came from
+    pub fn moddir_origin(&self) -> ValueOrigin {
+        match self.overrides.moddir {
+            Some(_) => ValueOrigin::Environment(MODDIR_ENV),
+            None => ValueOrigin::File(Self::path()),
+        }
+    }
+
+    pub fn set_moddir(&mut self, moddir: PathBuf) {
+        self.moddir = moddir;
     }
 
     pub fn profiles(&self) -> &Profiles {
@@ -58,8 +232,45 @@ impl ToolConfig {
     }
 }
 
-fn config_path() -> PathBuf {
-    crate::EXEDIR.join(FILE_NAME)
+/// Looks up the config file: --tool-config, CVMODTOOL_CONFIG, the user config directory
+/// then the executable directory. New configs are created in the user config directory.
+fn config_path() -> (PathBuf, ConfigSource) {
+    if let Some(path) = TOOL_CONFIG.get() {
+        return (crate::WORKDIR.join(path), ConfigSource::Argument);
+    }
+
+    if let Some(path) = std::env::var_os(CONFIG_ENV) {
+        return (crate::WORKDIR.join(path), ConfigSource::Environment);
+    }
+
+    let user = user_config_dir().map(|dir| dir.join(FILE_NAME));
+    let exe = crate::EXEDIR.join(FILE_NAME);
+
+    match user {
+        Some(user) if user.is_file() => (user, ConfigSource::UserDir),
+        _ if exe.is_file() => (exe, ConfigSource::ExeDir),
+        Some(user) => (user, ConfigSource::UserDir),
+        None => (exe, ConfigSource::ExeDir),
+    }
+}
+
+/// Platform config directory of the tool
+fn user_config_dir() -> Option<PathBuf> {
+    let var = |name| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let base = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))
+    };
+
+    base.map(|base| base.join("cvmodtool"))
 }
 
 #[derive(Debug, Error)]
@@ -72,4 +283,8 @@ pub enum ToolConfigError {
     Serialize(#[from] toml::ser::Error),
     #[error("Failed to save tool config. ({0})")]
     Write(#[source] std::io::Error),
+    #[error("There is no tool config file to save to, the values come from the environment variables! (Create it with the 'config init' command)")]
+    EnvOnly,
+    #[error("Invalid engine in tool config: {0}")]
+    Engine(#[from] EngineError),
 }
@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+
+/// Prefix of the lines repeated in the summary at the end of the cook
+const SUMMARY_PREFIX: &str = "LogInit: Display: ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "Error"),
+            Self::Warning => write!(f, "Warning"),
+        }
+    }
+}
+
+/// A warning/error found in the log
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Message {
+    pub severity: Severity,
+    /// Log category (e.g. LogCook) or UAT for the automation tool's own errors
+    pub category: String,
+    pub text: String,
+    /// Asset the message is about (e.g. /Game/Maps/work)
+    pub asset: Option<String>,
+}
+
+/// Warnings and errors of a UAT cook log
+#[derive(Debug, Default)]
+pub struct CookLog {
+    messages: Vec<Message>,
+    /// Result line of the cook (e.g. "Failure - 1 error(s), 4 warning(s)")
+    result: Option<String>,
+}
+
+impl CookLog {
+    /// Parses the UAT output. Messages repeated in the cook summary are only kept once.
+    pub fn parse(text: &str) -> Self {
+        let mut log = Self::default();
+        let mut seen = HashSet::new();
+
+        for line in text.lines() {
+            let line = line.trim();
+            let line = line.strip_prefix(SUMMARY_PREFIX).unwrap_or(line);
+
+            if line.starts_with("Success - ") || line.starts_with("Failure - ") {
+                log.result = Some(line.to_string());
+                continue;
+            }
+
+            if let Some(message) = parse_line(line) {
+                if seen.insert(message.clone()) {
+                    log.messages.push(message);
+                }
+            }
+        }
+
+        log
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| m.severity == severity)
+            .count()
+    }
+
+    /// Messages grouped by asset (None for the ones not about an asset), errors first
+    pub fn by_asset(&self) -> BTreeMap<Option<&str>, Vec<&Message>> {
+        let mut groups: BTreeMap<Option<&str>, Vec<&Message>> = BTreeMap::new();
+        for message in &self.messages {
+            groups
+                .entry(message.asset.as_deref())
+                .or_default()
+                .push(message);
+        }
+
+        for messages in groups.values_mut() {
+            messages.sort_by_key(|m| m.severity);
+        }
+        groups
+    }
+}
+
+/// Parses "Category: Warning: text", "Category: Error: text" and "ERROR: text" lines
+fn parse_line(line: &str) -> Option<Message> {
+    if let Some(text) = line.strip_prefix("ERROR: ") {
+        return Some(Message {
+            severity: Severity::Error,
+            category: "UAT".to_string(),
+            text: text.to_string(),
+            asset: asset(text),
+        });
+    }
+
+    let (category, rest) = line.split_once(": ")?;
+    if !category.starts_with("Log") || category.contains(' ') {
+        return None;
+    }
+
+    let (severity, text) = if let Some(text) = rest.strip_prefix("Warning: ") {
+        (Severity::Warning, text)
+    } else if let Some(text) = rest.strip_prefix("Error: ") {
+        (Severity::Error, text)
+    } else {
+        return None;
+    };
+
+    Some(Message {
+        severity,
+        category: category.to_string(),
+        text: text.to_string(),
+        asset: asset(text),
+    })
+}
+
+/// First game asset path in the text without the object name (/Game/A/B.B_C -> /Game/A/B)
+fn asset(text: &str) -> Option<String> {
+    let start = text.find("/Game/")?;
+    let path: String = text[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && !matches!(c, '\'' | '"' | '.' | ':' | ','))
+        .collect();
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let log = CookLog::parse(include_str!("samples/cook.log"));
+
+        assert_eq!(log.result(), Some("Failure - 1 error(s), 4 warning(s)"));
+        assert_eq!(log.count(Severity::Error), 2);
+        assert_eq!(log.count(Severity::Warning), 4);
+
+        let groups = log.by_asset();
+        let missing = &groups[&Some("/Game/ModResources/BP_Missing")];
+        assert_eq!(missing.len(), 2);
+        assert_eq!(missing[0].category, "LogLinker");
+
+        let ladder = &groups[&Some("/Game/ModResources/BP_Ladder")];
+        assert_eq!(ladder[0].severity, Severity::Error);
+        assert!(groups.contains_key(&Some("/Game/Maps/Old")));
+
+        // Cook failure and the slow shader compiler aren't about an asset
+        let other = &groups[&None];
+        assert_eq!(other[0].category, "UAT");
+        assert_eq!(other[1].category, "LogShaderCompilers");
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("LogCook: Display: Cooking /Game/Maps/work"),
+            None
+        );
+        assert_eq!(parse_line("Running: UE4Editor-Cmd.exe: Error: x"), None);
+        assert_eq!(
+            asset("Failed to load '/Game/Maps/work.work': x"),
+            Some("/Game/Maps/work".to_string())
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use crate::utils::native_path;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// RunUAT.bat relative to the engine install folder
+pub const UAT: &str = "Engine/Build/BatchFiles/RunUAT.bat";
+/// UnrealPak.exe relative to the engine install folder
+pub const UNREALPAK: &str = "Engine/Binaries/Win64/UnrealPak.exe";
+/// UnrealBuildTool.exe relative to the engine install folder
+pub const UBT: &str = "Engine/Binaries/DotNET/UnrealBuildTool.exe";
+/// Build.version relative to the engine install folder
+const BUILD_VERSION: &str = "Engine/Build/Build.version";
+
+/// Launcher file listing the engines installed by the Epic Games Launcher
+const LAUNCHER_INSTALLED: &str = "Epic/UnrealEngineLauncher/LauncherInstalled.dat";
+
+/// A verified UE 4.18 install
+#[derive(Debug, Clone)]
+pub struct Engine {
+    path: PathBuf,
+    version: String,
+}
+
+impl Engine {
+    /// Engine install folder
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Full engine version (e.g. 4.18.3)
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BuildVersion {
+    major_version: u32,
+    minor_version: u32,
+    patch_version: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LauncherInstalled {
+    #[serde(default)]
+    installation_list: Vec<Installation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Installation {
+    install_location: String,
+}
+
+/// Checks that the folder is a UE 4.18 install with the tools needed
+pub fn verify(path: &Path) -> Result<Engine, EngineError> {
+    for tool in [UAT, UNREALPAK] {
+        let tool = native_path(path.join(tool));
+        if !tool.is_file() {
+            return Err(EngineError::MissingTool(tool));
+        }
+    }
+
+    let file = native_path(path.join(BUILD_VERSION));
+    let content = std::fs::read_to_string(&file).map_err(|_| EngineError::MissingVersion(file))?;
+    let build: BuildVersion = json::from_str(&content).map_err(EngineError::InvalidVersion)?;
+    let version = format!(
+        "{}.{}.{}",
+        build.major_version, build.minor_version, build.patch_version
+    );
+
+    if (build.major_version, build.minor_version) != (4, 18) {
+        return Err(EngineError::WrongVersion(version));
+    }
+
+    Ok(Engine {
+        path: path.to_path_buf(),
+        version,
+    })
+}
+
+/// Finds the UE 4.18 installs from the launcher and the common install folders
+pub fn find() -> Vec<Engine> {
+    let mut candidates = vec![];
+    for root in roots() {
+        candidates.extend(launcher_installs(&root));
+
+        // Default install folder of the launcher
+        let epic_games = windows_path(&root, r"C:\Program Files\Epic Games");
+        if let Ok(dirs) = std::fs::read_dir(epic_games) {
+            candidates.extend(dirs.filter_map(|d| d.ok()).map(|d| d.path()));
+        }
+    }
+
+    let mut engines: Vec<Engine> = vec![];
+    for candidate in candidates {
+        let Ok(engine) = verify(&candidate) else {
+            continue;
+        };
+
+        let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        if !engines
+            .iter()
+            .any(|e| canonical(e.path()) == canonical(engine.path()))
+        {
+            engines.push(engine);
+        }
+    }
+    engines
+}
+
+/// Engine folders listed in LauncherInstalled.dat
+fn launcher_installs(root: &Path) -> Vec<PathBuf> {
+    let program_data = windows_path(root, r"C:\ProgramData");
+    let file = native_path(program_data.join(LAUNCHER_INSTALLED));
+
+    let Ok(content) = std::fs::read_to_string(file) else {
+        return vec![];
+    };
+    let Ok(launcher) = json::from_str::<LauncherInstalled>(&content) else {
+        return vec![];
+    };
+
+    launcher
+        .installation_list
+        .iter()
+        .map(|i| windows_path(root, &i.install_location))
+        .collect()
+}
+
+/// Windows system roots. On other platforms these are the Wine prefixes.
+fn roots() -> Vec<PathBuf> {
+    #[cfg(windows)]
+    return vec![PathBuf::new()];
+
+    #[cfg(not(windows))]
+    {
+        let mut prefixes = vec![];
+        if let Some(prefix) = std::env::var_os("WINEPREFIX") {
+            prefixes.push(PathBuf::from(prefix));
+        }
+        if let Some(home) = std::env::var_os("HOME") {
+            prefixes.push(PathBuf::from(home).join(".wine"));
+        }
+        prefixes.retain(|p| p.is_dir());
+        prefixes
+    }
+}
+
+/// Converts a Windows path to a path inside the root (Wine prefix)
+fn windows_path(root: &Path, path: &str) -> PathBuf {
+    if cfg!(windows) {
+        return PathBuf::from(path);
+    }
+
+    // Drives are linked in the dosdevices folder of the prefix (e.g. dosdevices/c:)
+    match path.split_once(':') {
+        Some((drive, rest)) if drive.len() == 1 => root
+            .join("dosdevices")
+            .join(format!("{}:", drive.to_ascii_lowercase()))
+            .join(native_path(rest.trim_start_matches(['\\', '/']))),
+        _ => native_path(path),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EngineError {
+    #[error("Engine tool not found: {0}")]
+    MissingTool(PathBuf),
+    #[error("Engine version file not found: {0}")]
+    MissingVersion(PathBuf),
+    #[error("Failed to parse engine version file. ({0})")]
+    InvalidVersion(#[source] json::Error),
+    #[error("Engine version is {0} instead of 4.18")]
+    WrongVersion(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_path() {
+        let path = windows_path(Path::new("/prefix"), r"D:\Epic Games\UE_4.18");
+        if cfg!(windows) {
+            assert_eq!(path, PathBuf::from(r"D:\Epic Games\UE_4.18"));
+        } else {
+            assert_eq!(
+                path,
+                PathBuf::from("/prefix/dosdevices/d:/Epic Games/UE_4.18")
+            );
+        }
+    }
+}
@@ -3,7 +3,13 @@ mod colored;
 
 mod commands;
 mod config;
+mod cooklog;
+mod engine;
+mod mods;
+mod pak;
+mod pattern;
 mod resources;
+mod steam;
 #[cfg(feature = "updater")]
 mod updater;
 mod utils;
@@ -21,9 +27,31 @@ fn main() {
 
     // Set verbose logging
     colored::USE_VERBOSE.set(opts.verbose()).unwrap();
+    utils::DRY_RUN.set(opts.dry_run()).unwrap();
+    if let Some(path) = opts.tool_config() {
+        config::TOOL_CONFIG.set(path.to_owned()).unwrap();
+    }
 
     // Check if tool config exist and interactively create it if it doesn't
-    if !ToolConfig::check() {
+    // (commands that don't need the engine or the mods directory work without it)
+    let needs_config = match opts.subcmd() {
+        SubCommand::Config(_)
+        | SubCommand::Doctor(_)
+        | SubCommand::Pak(_)
+        | SubCommand::Modules(_)
+        | SubCommand::Profiles(_) => false,
+        SubCommand::Package(cmd) => cmd.unrealpak(),
+        _ => true,
+    };
+    if needs_config && !ToolConfig::check() {
+        if opts.no_interactive() || opts.dry_run() {
+            error_exit(
+                -10,
+                "Failed to create tool config",
+                anyhow!("Tool config not found! Create it with the 'config init' command."),
+            );
+        }
+
         important!("Creating tool config:");
         if let Err(err) = create_tool_config() {
             error_exit(-10, "Failed to create tool config", err);
@@ -43,6 +71,14 @@ fn main() {
 
     #[cfg(feature = "updater")]
     if !resources::dir().is_dir() {
+        if opts.dry_run() {
+            error_exit(
+                -11,
+                "Failed to download resources",
+                anyhow!("Resources not found! Run without --dry-run to download them."),
+            );
+        }
+
         important!("Downloading resources:");
         let cmd = commands::update::Update::setup();
         if let Err(err) = cmd.execute() {
@@ -71,6 +107,59 @@ fn main() {
                 error_exit(-4, "Failed to install the package", err);
             }
         }
+        SubCommand::Run(cmd) => {
+            if let Err(err) = cmd.validate() {
+                err.exit();
+            }
+            if let Err((stage, err)) = cmd.execute() {
+                error_exit(stage.exit_code(), stage.failure_message(), err);
+            }
+        }
+        SubCommand::Project(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-9, "Failed to modify the project", err);
+            }
+        }
+        SubCommand::Pak(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-6, "Failed to read the pak file", err);
+            }
+        }
+        SubCommand::Conflicts(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-7, "Failed to check conflicts", err);
+            }
+        }
+        SubCommand::Mods(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-8, "Failed to manage mods", err);
+            }
+        }
+        SubCommand::Modules(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-12, "Failed to list modules", err);
+            }
+        }
+        SubCommand::Profiles(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-13, "Failed to list profiles", err);
+            }
+        }
+        SubCommand::Config(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-14, "Failed to manage the tool config", err);
+            }
+        }
+        SubCommand::Doctor(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-15, "Environment check failed", err);
+            }
+        }
+        SubCommand::Watch(cmd) => {
+            if let Err(err) = cmd.execute() {
+                error_exit(-16, "Failed to watch the project", err);
+            }
+        }
         #[cfg(feature = "updater")]
         SubCommand::Update(cmd) => {
             if let Err(err) = cmd.execute() {
@@ -87,28 +176,68 @@ fn error_exit(code: i32, msg: &str, err: Error) {
 
 fn create_tool_config() -> Result<()> {
     let mut buffer = String::new();
-    info!("Path to UE 4.18:");
-    verbose!("This folder should contain the \"Engine\" directory");
-    verbose!(r"Example: C:\Engines\Unreal\UE_4.18");
+    let detected = engine::find();
+    if !detected.is_empty() {
+        info!("Detected UE 4.18 installs:");
+        for (i, engine) in detected.iter().enumerate() {
+            info!(
+                "  [{}] {} ({})",
+                i + 1,
+                engine.path().display(),
+                engine.version()
+            );
+        }
+        info!("Path to UE 4.18 (or the number of a detected one, leave empty to use the first):");
+    } else {
+        info!("Path to UE 4.18:");
+        verbose!("This folder should contain the \"Engine\" directory");
+        verbose!(r"Example: C:\Engines\Unreal\UE_4.18");
+    }
     std::io::stdin().read_line(&mut buffer)?;
-    let engine = PathBuf::from(buffer.trim_end());
-    if !engine.is_dir() {
-        return Err(anyhow!("Engine directory is not a valid direcoty"));
+    let input = buffer.trim_end();
+    let engine = match input.parse::<usize>() {
+        Ok(i) if (1..=detected.len()).contains(&i) => detected[i - 1].path().to_owned(),
+        _ if input.is_empty() && !detected.is_empty() => detected[0].path().to_owned(),
+        _ => PathBuf::from(input),
+    };
+    if let Err(err) = engine::verify(&engine) {
+        return Err(anyhow!(
+            "Engine directory is not a valid UE 4.18 install: {err}"
+        ));
     }
 
     buffer.clear();
-    info!("Path to Code Vein \"~mods\" folder:");
-    verbose!(
-        r"Example: C:\Program Files (x86)\Steam\steamapps\common\CODE VEIN\CodeVein\Content\Paks\~mods",
-    );
+    let detected = steam::find_game().map(|game| steam::moddir(&game));
+    match &detected {
+        Some(moddir) => {
+            info!("Detected Code Vein \"~mods\" folder: {}", moddir.display());
+            info!("Path to Code Vein \"~mods\" folder (leave empty to use the detected one):");
+        }
+        None => {
+            info!("Path to Code Vein \"~mods\" folder:");
+            verbose!(
+                r"Example: C:\Program Files (x86)\Steam\steamapps\common\CODE VEIN\CodeVein\Content\Paks\~mods",
+            );
+        }
+    }
     std::io::stdin().read_line(&mut buffer)?;
-    let moddir = PathBuf::from(buffer.trim_end());
+    let moddir = match (buffer.trim_end(), detected) {
+        ("", Some(detected)) => {
+            // The ~mods folder doesn't exist until the first mod is installed
+            if !detected.is_dir() {
+                std::fs::create_dir_all(&detected)?;
+            }
+            detected
+        }
+        (input, _) => PathBuf::from(input),
+    };
     if !moddir.is_dir() {
         return Err(anyhow!("Mods directory is not a valid direcoty"));
     }
 
     let config = ToolConfig::new(engine, moddir);
     config.save()?;
+    info!("Tool config saved to {}", ToolConfig::path().display());
 
     Ok(())
 }
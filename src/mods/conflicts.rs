@@ -0,0 +1,140 @@
+use crate::pak::PakReader;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Asset that is present in more than one pak
+#[derive(Debug)]
+pub struct Conflict {
+    asset: String,
+    paks: Vec<PathBuf>,
+}
+
+impl Conflict {
+    /// Asset path (without extension)
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
+
+    /// Paks containing the asset in load order
+    pub fn paks(&self) -> &[PathBuf] {
+        &self.paks
+    }
+
+    /// The pak whose version of the asset gets loaded
+    pub fn winner(&self) -> &PathBuf {
+        self.paks.last().expect("Conflict without paks!")
+    }
+
+    /// Checks if the pak is involved in the conflict
+    pub fn involves<P: AsRef<Path>>(&self, pak: P) -> bool {
+        self.paks.iter().any(|p| p == pak.as_ref())
+    }
+}
+
+/// Finds the assets overridden by more than one pak. The paks must be in load order.
+pub fn find(paks: &[PathBuf]) -> Vec<Conflict> {
+    // Lowercase asset path -> (asset path, pak indices)
+    let mut assets: BTreeMap<String, (String, Vec<usize>)> = BTreeMap::new();
+
+    for (index, pak) in paks.iter().enumerate() {
+        let reader = match PakReader::open(pak) {
+            Ok(reader) => reader,
+            Err(err) => {
+                warning!("Failed to read {}: {}", pak.display(), err);
+                continue;
+            }
+        };
+
+        for (name, _) in reader.entries() {
+            let asset = asset_path(reader.mount_point(), name);
+            let (_, indices) = assets
+                .entry(asset.to_lowercase())
+                .or_insert_with(|| (asset, vec![]));
+            if indices.last() != Some(&index) {
+                indices.push(index);
+            }
+        }
+    }
+
+    assets
+        .into_values()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(asset, indices)| Conflict {
+            asset,
+            paks: indices.into_iter().map(|i| paks[i].clone()).collect(),
+        })
+        .collect()
+}
+
+/// Full asset path of a pak entry without the mount point prefix and the extension.
+/// (.uasset, .uexp and .ubulk files belong to the same asset)
+fn asset_path(mount_point: &str, name: &str) -> String {
+    let full = format!("{mount_point}{name}").replace('\\', "/");
+    let full = full.trim_start_matches("../");
+
+    match full.rfind('.') {
+        Some(dot) if !full[dot..].contains('/') => full[..dot].to_string(),
+        _ => full.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pak::PakWriter;
+
+    fn create_pak(dir: &Path, name: &str, entries: &[&str]) -> PathBuf {
+        let path = dir.join(name);
+        let mut writer = PakWriter::create(&path, false).unwrap();
+        for entry in entries {
+            writer.add(entry, b"data").unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let dir = std::env::temp_dir().join("cvmodtool_test_conflicts");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        create_pak(
+            &dir,
+            "Z_Map_P.pak",
+            &[
+                "CodeVein/Content/Maps/work.umap",
+                "CodeVein/Content/ModResources/Ladder.uasset",
+                "CodeVein/Content/ModResources/Ladder.uexp",
+            ],
+        );
+        create_pak(
+            &dir,
+            "A_Other_P.pak",
+            &["CodeVein/Content/modresources/ladder.uasset"],
+        );
+        create_pak(&dir, "Z_Regular.pak", &["CodeVein/Content/Maps/work.umap"]);
+        create_pak(&dir, "B_Upper.PAK", &["CodeVein/Content/Other.uasset"]);
+
+        let paks = crate::mods::find_paks(&dir).unwrap();
+        let names: Vec<_> = paks.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(
+            names,
+            [
+                "B_Upper.PAK",
+                "Z_Regular.pak",
+                "A_Other_P.pak",
+                "Z_Map_P.pak"
+            ]
+        );
+
+        let conflicts = find(&paks);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].asset(), "CodeVein/Content/Maps/work");
+        assert!(conflicts[0].winner().ends_with("Z_Map_P.pak"));
+        assert_eq!(conflicts[1].paks().len(), 2);
+        assert!(conflicts[1].winner().ends_with("Z_Map_P.pak"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,300 @@
+pub mod conflicts;
+mod registry;
+
+pub use registry::{Record, Registry};
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Manages the paks inside the mods directory
+pub struct ModManager {
+    moddir: PathBuf,
+    disabled: PathBuf,
+    registry: Registry,
+}
+
+/// Pak file found in the mods directory or in the disabled directory
+#[derive(Debug)]
+pub struct InstalledMod {
+    pub path: PathBuf,
+    pub enabled: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub record: Option<Record>,
+}
+
+impl InstalledMod {
+    /// Pak file name
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+impl ModManager {
+    pub fn new<P: AsRef<Path>>(moddir: P) -> Result<Self> {
+        let moddir = moddir.as_ref().to_path_buf();
+        let disabled = disabled_dir(&moddir)?;
+        let registry = Registry::load()?;
+
+        if let Some(legacy) = legacy_disabled_dir(&moddir).filter(|d| *d != disabled) {
+            if find_paks(&legacy).is_ok_and(|paks| !paks.is_empty()) {
+                warning!(
+                    "The paks in {} are still loaded by the game! Move them to {} to disable them.",
+                    legacy.display(),
+                    disabled.display()
+                );
+            }
+        }
+
+        Ok(Self {
+            moddir,
+            disabled,
+            registry,
+        })
+    }
+
+    /// Directory of the disabled paks
+    pub fn disabled_dir(&self) -> &PathBuf {
+        &self.disabled
+    }
+
+    /// Lists the enabled and disabled paks
+    pub fn list(&self) -> Result<Vec<InstalledMod>> {
+        let mut mods = vec![];
+
+        for (dir, enabled) in [(&self.moddir, true), (&self.disabled, false)] {
+            if !dir.is_dir() {
+                continue;
+            }
+
+            for path in find_paks(dir)? {
+                let metadata = std::fs::metadata(&path)?;
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let record = self.registry.get(&name).cloned();
+                mods.push(InstalledMod {
+                    enabled,
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                    record,
+                    path,
+                });
+            }
+        }
+
+        Ok(mods)
+    }
+
+    /// Copies the pak into the mods directory and records the project it came from
+    pub fn install(&mut self, pakfile: &Path, project: Option<(&str, &Path)>) -> Result<PathBuf> {
+        let name = pakfile
+            .file_name()
+            .ok_or_else(|| anyhow!("Failed to get the .pak file name"))?
+            .to_string_lossy()
+            .into_owned();
+
+        let target = self.moddir.join(&name);
+        if crate::utils::dry_run() {
+            planned!("Copy file: {} -> {}", pakfile.display(), target.display());
+            return Ok(target);
+        }
+
+        std::fs::copy(pakfile, &target).map_err(|e| anyhow!("Failed to copy .pak file: {e}"))?;
+
+        // Remove the old disabled copy so it doesn't shadow the new one on enable
+        let disabled = self.disabled.join(&name);
+        if disabled.is_file() {
+            verbose!("Removing disabled copy: {}", disabled.display());
+            std::fs::remove_file(disabled)?;
+        }
+
+        if let Some((project, path)) = project {
+            let installed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            self.registry.insert(
+                name,
+                Record {
+                    project: project.to_string(),
+                    path: path.to_path_buf(),
+                    installed,
+                },
+            );
+            // The pak is installed already, only its origin is lost
+            if let Err(err) = self.registry.save() {
+                warning!("{err}");
+            }
+        }
+
+        Ok(target)
+    }
+
+    /// Deletes the pak (enabled or disabled)
+    pub fn uninstall(&mut self, name: &str) -> Result<PathBuf> {
+        let name = pak_name(name);
+        let path = self.find(&name)?;
+        if crate::utils::dry_run() {
+            planned!("Delete file: {}", path.display());
+            return Ok(path);
+        }
+
+        std::fs::remove_file(&path)?;
+
+        if self.registry.remove(&name).is_some() {
+            if let Err(err) = self.registry.save() {
+                warning!("{err}");
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Moves the pak back into the mods directory
+    pub fn enable(&self, name: &str) -> Result<PathBuf> {
+        let name = pak_name(name);
+        let source = self.disabled.join(&name);
+        if !source.is_file() {
+            return Err(anyhow!("Disabled mod not found: {name}"));
+        }
+
+        let target = self.moddir.join(&name);
+        if target.exists() {
+            return Err(anyhow!("Mod is already enabled: {name}"));
+        }
+
+        if crate::utils::dry_run() {
+            planned!("Move file: {} -> {}", source.display(), target.display());
+            return Ok(target);
+        }
+
+        std::fs::rename(source, &target)?;
+        Ok(target)
+    }
+
+    /// Moves the pak into the disabled directory
+    pub fn disable(&self, name: &str) -> Result<PathBuf> {
+        let name = pak_name(name);
+        let source = self.moddir.join(&name);
+        if !source.is_file() {
+            return Err(anyhow!("Enabled mod not found: {name}"));
+        }
+
+        let target = self.disabled.join(&name);
+        if crate::utils::dry_run() {
+            planned!("Move file: {} -> {}", source.display(), target.display());
+            return Ok(target);
+        }
+
+        std::fs::create_dir_all(&self.disabled)?;
+        std::fs::rename(source, &target)?;
+        Ok(target)
+    }
+
+    fn find(&self, name: &str) -> Result<PathBuf> {
+        [&self.moddir, &self.disabled]
+            .into_iter()
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| anyhow!("Mod not found: {name}"))
+    }
+}
+
+/// Directory of the disabled paks. The game mounts every pak under Content/Paks (including the
+/// subfolders), so for `Content/Paks/~mods` it is `Content/~mods_disabled`. Otherwise it's a sibling.
+fn disabled_dir(moddir: &Path) -> Result<PathBuf> {
+    let name = moddir
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid mods directory: {}", moddir.display()))?;
+    let mut disabled = name.to_os_string();
+    disabled.push("_disabled");
+
+    let paks = moddir.ancestors().find(|dir| {
+        dir.file_name()
+            .is_some_and(|n| n.eq_ignore_ascii_case("Paks"))
+    });
+    match paks.and_then(Path::parent) {
+        Some(content) => Ok(content.join(disabled)),
+        None => Ok(moddir.with_file_name(disabled)),
+    }
+}
+
+/// Disabled directory of older versions (a sibling inside Content/Paks, still mounted by the game)
+fn legacy_disabled_dir(moddir: &Path) -> Option<PathBuf> {
+    let mut disabled = moddir.file_name()?.to_os_string();
+    disabled.push("_disabled");
+    Some(moddir.with_file_name(disabled))
+}
+
+/// Appends the .pak extension if it is missing
+pub fn pak_name(name: &str) -> String {
+    if name.to_lowercase().ends_with(".pak") {
+        name.to_string()
+    } else {
+        format!("{name}.pak")
+    }
+}
+
+/// Finds the .pak files directly inside the directory
+pub fn find_paks<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut paks = vec![];
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let is_pak = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pak"));
+        if path.is_file() && is_pak {
+            paks.push(path);
+        }
+    }
+
+    sort_load_order(&mut paks);
+    Ok(paks)
+}
+
+/// Sorts the paks by load order. Later paks override the earlier ones.
+///
+/// Patch paks (name ending in `_P`) take priority over regular paks,
+/// otherwise the paks are mounted in alphabetical order.
+pub fn sort_load_order(paks: &mut [PathBuf]) {
+    paks.sort_by_cached_key(|p| {
+        let stem = p
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        (stem.ends_with("_p"), stem)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_dir() {
+        let moddir = Path::new("/Games/CODE VEIN/CodeVein/Content/Paks/~mods");
+        let disabled = disabled_dir(moddir).unwrap();
+        assert_eq!(
+            disabled,
+            Path::new("/Games/CODE VEIN/CodeVein/Content/~mods_disabled")
+        );
+        assert!(!disabled.starts_with("/Games/CODE VEIN/CodeVein/Content/Paks"));
+
+        // Nested mods folders are still taken out of the Paks folder
+        let moddir = Path::new("/Games/CodeVein/Content/paks/~mods/test");
+        assert_eq!(
+            disabled_dir(moddir).unwrap(),
+            Path::new("/Games/CodeVein/Content/test_disabled")
+        );
+
+        // Mods folder outside of the game
+        let moddir = Path::new("/tmp/mods");
+        assert_eq!(
+            disabled_dir(moddir).unwrap(),
+            Path::new("/tmp/mods_disabled")
+        );
+    }
+}
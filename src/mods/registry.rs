@@ -0,0 +1,72 @@
+use crate::config::ToolConfig;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "installed.toml";
+
+/// Record of the paks installed from cvmod projects
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default)]
+    paks: BTreeMap<String, Record>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// Name of the Unreal project
+    pub project: String,
+    /// Project directory
+    pub path: PathBuf,
+    /// Install time in seconds since the Unix epoch
+    pub installed: u64,
+}
+
+impl Registry {
+    /// Loads the registry. Returns an empty registry if it doesn't exist yet.
+    pub fn load() -> Result<Self> {
+        // Older versions saved it next to the executable
+        let path = [file(), crate::EXEDIR.join(FILE_NAME)]
+            .into_iter()
+            .find(|path| path.is_file());
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read install registry: {e}"))?;
+        toml::from_str(&content).map_err(|e| anyhow!("Failed to parse install registry: {e}"))
+    }
+
+    /// Saves the registry next to the tool config
+    pub fn save(&self) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        let path = file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| anyhow!("Failed to save install registry: {e}"))?;
+        }
+        std::fs::write(path, content).map_err(|e| anyhow!("Failed to save install registry: {e}"))
+    }
+
+    /// Gets the record of a pak file
+    pub fn get(&self, pak: &str) -> Option<&Record> {
+        self.paks.get(pak)
+    }
+
+    /// Records a pak file
+    pub fn insert(&mut self, pak: String, record: Record) {
+        self.paks.insert(pak, record);
+    }
+
+    /// Removes the record of a pak file
+    pub fn remove(&mut self, pak: &str) -> Option<Record> {
+        self.paks.remove(pak)
+    }
+}
+
+/// Registry file next to the tool config (user config directory by default)
+fn file() -> PathBuf {
+    ToolConfig::path().with_file_name(FILE_NAME)
+}
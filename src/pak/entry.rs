@@ -0,0 +1,144 @@
+use super::{read_u32, read_u64, Hash};
+use std::fmt::Display;
+use std::io::{Read, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Zlib,
+    Unknown(i32),
+}
+
+impl Compression {
+    fn id(self) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Zlib => 1,
+            Self::Unknown(id) => id,
+        }
+    }
+
+    fn from_id(id: i32) -> Self {
+        match id {
+            0 => Self::None,
+            1 => Self::Zlib,
+            id => Self::Unknown(id),
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Zlib => write!(f, "Zlib"),
+            Self::Unknown(id) => write!(f, "Unknown ({id})"),
+        }
+    }
+}
+
+/// Compressed block range (absolute offsets in the pak file)
+#[derive(Debug, Clone, Copy)]
+pub struct Block {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Pak file entry (FPakEntry)
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Offset of the entry header in the pak file
+    pub offset: u64,
+    /// Stored (possibly compressed) size
+    pub size: u64,
+    /// Uncompressed size
+    pub uncompressed_size: u64,
+    pub compression: Compression,
+    /// SHA1 of the stored data
+    pub hash: Hash,
+    pub blocks: Vec<Block>,
+    pub encrypted: bool,
+    pub block_size: u32,
+}
+
+impl Entry {
+    /// Serialized size of an entry with the specified amount of compression blocks
+    pub fn serialized_size(compression: Compression, block_count: usize) -> u64 {
+        let mut size = 8 + 8 + 8 + 4 + 20 + 1 + 4;
+        if compression != Compression::None {
+            size += 4 + 16 * block_count as u64;
+        }
+        size
+    }
+
+    /// Reads an entry serialized with the specified pak version
+    pub fn read<R: Read>(reader: &mut R, version: i32) -> std::io::Result<Self> {
+        let offset = read_u64(reader)?;
+        let size = read_u64(reader)?;
+        let uncompressed_size = read_u64(reader)?;
+        let compression = Compression::from_id(read_u32(reader)? as i32);
+
+        if version <= 1 {
+            read_u64(reader)?; // Timestamp
+        }
+
+        let mut hash = [0; 20];
+        reader.read_exact(&mut hash)?;
+
+        let mut blocks = vec![];
+        let mut encrypted = false;
+        let mut block_size = 0;
+
+        if version >= 3 {
+            if compression != Compression::None {
+                let count = read_u32(reader)?;
+                for _ in 0..count {
+                    let mut start = read_u64(reader)?;
+                    let mut end = read_u64(reader)?;
+                    // Block offsets are relative to the entry since version 5
+                    if version >= 5 {
+                        start += offset;
+                        end += offset;
+                    }
+                    blocks.push(Block { start, end });
+                }
+            }
+
+            let mut flag = [0];
+            reader.read_exact(&mut flag)?;
+            encrypted = flag[0] != 0;
+            block_size = read_u32(reader)?;
+        }
+
+        Ok(Self {
+            offset,
+            size,
+            uncompressed_size,
+            compression,
+            hash,
+            blocks,
+            encrypted,
+            block_size,
+        })
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.offset.to_le_bytes())?;
+        writer.write_all(&self.size.to_le_bytes())?;
+        writer.write_all(&self.uncompressed_size.to_le_bytes())?;
+        writer.write_all(&self.compression.id().to_le_bytes())?;
+        writer.write_all(&self.hash)?;
+
+        if self.compression != Compression::None {
+            writer.write_all(&(self.blocks.len() as i32).to_le_bytes())?;
+            for block in &self.blocks {
+                writer.write_all(&block.start.to_le_bytes())?;
+                writer.write_all(&block.end.to_le_bytes())?;
+            }
+        }
+
+        writer.write_all(&[self.encrypted as u8])?;
+        writer.write_all(&self.block_size.to_le_bytes())?;
+        Ok(())
+    }
+}
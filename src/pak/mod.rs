@@ -0,0 +1,127 @@
+mod entry;
+mod reader;
+mod writer;
+
+pub use entry::{Compression, Entry};
+pub use reader::PakReader;
+pub use writer::PakWriter;
+
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// Magic number at the start of the pak footer
+pub const MAGIC: u32 = 0x5A6F12E1;
+/// Pak version used by UE 4.18 (PakFile_Version_IndexEncryption)
+pub const VERSION: i32 = 4;
+/// Mount point used by Code Vein mods
+pub const MOUNT_POINT: &str = "../../../";
+/// Size of the zlib compression blocks
+pub const COMPRESSION_BLOCK_SIZE: u32 = 0x10000;
+
+/// Serialized size of the pak footer (without the encrypted index flag)
+const FOOTER_SIZE: u64 = 4 + 4 + 8 + 8 + 20;
+
+pub type Hash = [u8; 20];
+
+/// Calculates the SHA1 hash of the data
+pub fn sha1(data: &[u8]) -> Hash {
+    sha1_smol::Sha1::from(data).digest().bytes()
+}
+
+/// Formats the hash as a hex string
+pub fn hash_hex(hash: &Hash) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Reads the specified amount of bytes. The buffer grows with the data read, so a corrupt
+/// length can't cause a huge allocation.
+fn read_bytes<R: Read>(reader: &mut R, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    reader.take(len).read_to_end(&mut buf)?;
+    if (buf.len() as u64) < len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(buf)
+}
+
+/// Reads an Unreal FString
+fn read_string<R: Read>(reader: &mut R) -> Result<String, PakError> {
+    let len = read_u32(reader)? as i32;
+    if len == 0 {
+        return Ok(String::new());
+    }
+
+    let value = if len > 0 {
+        let mut buf = read_bytes(reader, len as u64)?;
+        buf.pop(); // Null terminator
+        String::from_utf8(buf).map_err(|_| PakError::InvalidString)?
+    } else {
+        let buf = read_bytes(reader, len.unsigned_abs() as u64 * 2)?;
+        let mut chars: Vec<u16> = buf
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        chars.pop(); // Null terminator
+        String::from_utf16(&chars).map_err(|_| PakError::InvalidString)?
+    };
+
+    Ok(value)
+}
+
+/// Writes an Unreal FString
+fn write_string<W: Write>(writer: &mut W, value: &str) -> std::io::Result<()> {
+    if value.is_ascii() {
+        let len = value.len() as i32 + 1;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(value.as_bytes())?;
+        writer.write_all(&[0])?;
+    } else {
+        // Non-ASCII strings are stored as UTF-16 with negative length
+        let chars: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+        let len = -(chars.len() as i32);
+        writer.write_all(&len.to_le_bytes())?;
+        for c in chars {
+            writer.write_all(&c.to_le_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum PakError {
+    #[error("I/O error. ({0})")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid entry name: {0}")]
+    InvalidName(String),
+    #[error("Duplicate entry: {0}")]
+    Duplicate(String),
+    #[error("Not a valid pak file!")]
+    InvalidMagic,
+    #[error("Unsupported pak version: {0}")]
+    UnsupportedVersion(i32),
+    #[error("Encrypted paks are not supported!")]
+    Encrypted,
+    #[error("Unsupported compression method: {0}")]
+    UnsupportedCompression(Compression),
+    #[error("Invalid pak index location!")]
+    InvalidIndex,
+    #[error("Invalid string in pak index!")]
+    InvalidString,
+    #[error("Hash mismatch for {0}!")]
+    HashMismatch(String),
+    #[error("Invalid compression blocks for {0}!")]
+    InvalidBlocks(String),
+}
@@ -0,0 +1,223 @@
+use super::{
+    read_bytes, read_u32, read_u64, Compression, Entry, Hash, PakError, FOOTER_SIZE, MAGIC,
+};
+use flate2::read::ZlibDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Oldest and newest supported pak versions. (UE 4.18 writes version 4, version 6 added
+/// delete records and version 7 the encryption key GUID to the footer)
+const MIN_VERSION: i32 = 1;
+const MAX_VERSION: i32 = 5;
+
+/// Reads the index and entries of .pak files
+pub struct PakReader<R: Read + Seek> {
+    reader: R,
+    version: i32,
+    mount_point: String,
+    index_hash: Hash,
+    entries: Vec<(String, Entry)>,
+}
+
+impl PakReader<BufReader<File>> {
+    /// Opens a pak file from disk
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PakError> {
+        let file = File::open(path)?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> PakReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, PakError> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        if len < FOOTER_SIZE {
+            return Err(PakError::InvalidMagic);
+        }
+
+        reader.seek(SeekFrom::Start(len - FOOTER_SIZE))?;
+        if read_u32(&mut reader)? != MAGIC {
+            return Err(PakError::InvalidMagic);
+        }
+
+        let version = read_u32(&mut reader)? as i32;
+        if !(MIN_VERSION..=MAX_VERSION).contains(&version) {
+            return Err(PakError::UnsupportedVersion(version));
+        }
+
+        let index_offset = read_u64(&mut reader)?;
+        let index_size = read_u64(&mut reader)?;
+        let mut index_hash = [0; 20];
+        reader.read_exact(&mut index_hash)?;
+
+        // Encrypted index flag precedes the footer since version 4
+        let mut footer_start = len - FOOTER_SIZE;
+        if version >= 4 {
+            footer_start = footer_start.checked_sub(1).ok_or(PakError::InvalidMagic)?;
+            reader.seek(SeekFrom::Start(footer_start))?;
+            let mut flag = [0];
+            reader.read_exact(&mut flag)?;
+            if flag[0] != 0 {
+                return Err(PakError::Encrypted);
+            }
+        }
+
+        // The index is between the data and the footer
+        let index_end = index_offset.checked_add(index_size);
+        if index_end.is_none_or(|end| end > footer_start) {
+            return Err(PakError::InvalidIndex);
+        }
+
+        reader.seek(SeekFrom::Start(index_offset))?;
+        let index = read_bytes(&mut reader, index_size)?;
+        if super::sha1(&index) != index_hash {
+            return Err(PakError::HashMismatch("index".to_string()));
+        }
+
+        let mut cursor = index.as_slice();
+        let mount_point = super::read_string(&mut cursor)?;
+        let count = read_u32(&mut cursor)?;
+        // Not preallocated, the count is only trusted as far as the index has entries
+        let mut entries = vec![];
+        for _ in 0..count {
+            let name = super::read_string(&mut cursor)?;
+            let entry = Entry::read(&mut cursor, version)?;
+            entries.push((name, entry));
+        }
+
+        Ok(Self {
+            reader,
+            version,
+            mount_point,
+            index_hash,
+            entries,
+        })
+    }
+
+    /// Pak file version
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// Mount point of the entries
+    pub fn mount_point(&self) -> &str {
+        &self.mount_point
+    }
+
+    /// SHA1 hash of the index
+    pub fn index_hash(&self) -> &Hash {
+        &self.index_hash
+    }
+
+    /// Entries with their names relative to the mount point
+    pub fn entries(&self) -> &[(String, Entry)] {
+        &self.entries
+    }
+
+    /// Reads and decompresses the data of an entry
+    pub fn read(&mut self, name: &str, entry: &Entry) -> Result<Vec<u8>, PakError> {
+        if entry.encrypted {
+            return Err(PakError::Encrypted);
+        }
+
+        // Skip the entry header preceding the data
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        Entry::read(&mut self.reader, self.version)?;
+
+        let stored = read_bytes(&mut self.reader, entry.size)?;
+        if super::sha1(&stored) != entry.hash {
+            return Err(PakError::HashMismatch(name.to_string()));
+        }
+
+        match entry.compression {
+            Compression::None => Ok(stored),
+            Compression::Zlib => {
+                let base = entry.blocks.first().map(|b| b.start).unwrap_or_default();
+                let mut data = vec![];
+                for block in &entry.blocks {
+                    let invalid = || PakError::InvalidBlocks(name.to_string());
+                    let start = block.start.checked_sub(base).ok_or_else(invalid)? as usize;
+                    let end = block.end.checked_sub(base).ok_or_else(invalid)? as usize;
+                    let compressed = stored.get(start..end).ok_or_else(invalid)?;
+                    ZlibDecoder::new(compressed).read_to_end(&mut data)?;
+                }
+                Ok(data)
+            }
+            other => Err(PakError::UnsupportedCompression(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pak::PakWriter;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_roundtrip() {
+        let large: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+        let mut writer = PakWriter::new(Cursor::new(vec![]), true);
+        writer.add("CodeVein/Content/Large.uasset", &large).unwrap();
+        writer.add("CodeVein/Content/Small.uexp", b"x").unwrap();
+        writer
+            .add("CodeVein/Content/Ünicode.uasset", b"data")
+            .unwrap();
+        let cursor = writer.finish().unwrap();
+
+        let mut reader = PakReader::new(cursor).unwrap();
+        assert_eq!(reader.version(), 4);
+        assert_eq!(reader.mount_point(), "../../../");
+
+        let entries = reader.entries().to_vec();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].1.compression, Compression::Zlib);
+        assert_eq!(entries[0].1.blocks.len(), 4);
+        assert_eq!(entries[1].1.compression, Compression::None);
+        assert_eq!(entries[2].0, "CodeVein/Content/Ünicode.uasset");
+
+        assert_eq!(reader.read(&entries[0].0, &entries[0].1).unwrap(), large);
+        assert_eq!(reader.read(&entries[1].0, &entries[1].1).unwrap(), b"x");
+        assert_eq!(reader.read(&entries[2].0, &entries[2].1).unwrap(), b"data");
+    }
+
+    /// Pak with the footer field at the offset (from the start of the footer) replaced
+    fn corrupt(offset: usize, value: &[u8]) -> Cursor<Vec<u8>> {
+        let mut writer = PakWriter::new(Cursor::new(vec![]), false);
+        writer.add("CodeVein/Content/Test.uasset", b"data").unwrap();
+        let mut pak = writer.finish().unwrap().into_inner();
+
+        let start = pak.len() - FOOTER_SIZE as usize + offset;
+        pak[start..start + value.len()].copy_from_slice(value);
+        Cursor::new(pak)
+    }
+
+    #[test]
+    fn test_corrupt() {
+        // Index size
+        let reader = PakReader::new(corrupt(16, &u64::MAX.to_le_bytes()));
+        assert!(matches!(reader, Err(PakError::InvalidIndex)));
+        let reader = PakReader::new(corrupt(16, &(1u64 << 40).to_le_bytes()));
+        assert!(matches!(reader, Err(PakError::InvalidIndex)));
+
+        // Index offset
+        let reader = PakReader::new(corrupt(8, &u64::MAX.to_le_bytes()));
+        assert!(matches!(reader, Err(PakError::InvalidIndex)));
+
+        // Version
+        let reader = PakReader::new(corrupt(4, &7u32.to_le_bytes()));
+        assert!(matches!(reader, Err(PakError::UnsupportedVersion(7))));
+
+        // Block starting before the first one
+        let mut writer = PakWriter::new(Cursor::new(vec![]), true);
+        writer
+            .add("CodeVein/Content/Test.uasset", &[7; 200_000])
+            .unwrap();
+        let mut reader = PakReader::new(writer.finish().unwrap()).unwrap();
+        let (name, mut entry) = reader.entries()[0].clone();
+        entry.blocks[1].start = entry.blocks[0].start - 1;
+        let data = reader.read(&name, &entry);
+        assert!(matches!(data, Err(PakError::InvalidBlocks(_))));
+    }
+}
@@ -0,0 +1,192 @@
+use super::entry::Block;
+use super::{Compression, Entry, PakError, COMPRESSION_BLOCK_SIZE, MAGIC, MOUNT_POINT, VERSION};
+use flate2::write::ZlibEncoder;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::path::Path;
+
+/// Writes UE 4.18 compatible (version 4) .pak files
+pub struct PakWriter<W: Write + Seek> {
+    writer: W,
+    compress: bool,
+    entries: Vec<(String, Entry)>,
+    names: HashSet<String>,
+}
+
+impl PakWriter<BufWriter<File>> {
+    /// Creates a new pak file on disk
+    pub fn create<P: AsRef<Path>>(path: P, compress: bool) -> Result<Self, PakError> {
+        let file = File::create(path)?;
+        Ok(Self::new(BufWriter::new(file), compress))
+    }
+}
+
+impl<W: Write + Seek> PakWriter<W> {
+    pub fn new(writer: W, compress: bool) -> Self {
+        Self {
+            writer,
+            compress,
+            entries: vec![],
+            names: HashSet::new(),
+        }
+    }
+
+    /// Adds a file to the pak. The name is relative to the mount point.
+    pub fn add(&mut self, name: &str, data: &[u8]) -> Result<(), PakError> {
+        let name = name.replace('\\', "/");
+        if name.is_empty() || name.starts_with('/') || name.split('/').any(|c| c == "..") {
+            return Err(PakError::InvalidName(name));
+        }
+        if self.names.contains(&name) {
+            return Err(PakError::Duplicate(name));
+        }
+
+        let offset = self.writer.stream_position()?;
+
+        let compressed = if self.compress && !data.is_empty() {
+            Some(compress_blocks(data)?)
+        } else {
+            None
+        };
+
+        let entry = match compressed {
+            // Only keep the compressed version if it is actually smaller
+            Some(blocks) if blocks.iter().map(Vec::len).sum::<usize>() < data.len() => {
+                let header_size = Entry::serialized_size(Compression::Zlib, blocks.len());
+                let mut start = offset + header_size;
+                let mut ranges = Vec::with_capacity(blocks.len());
+                for block in &blocks {
+                    let end = start + block.len() as u64;
+                    ranges.push(Block { start, end });
+                    start = end;
+                }
+
+                let stored = blocks.concat();
+                let entry = Entry {
+                    offset,
+                    size: stored.len() as u64,
+                    uncompressed_size: data.len() as u64,
+                    compression: Compression::Zlib,
+                    hash: super::sha1(&stored),
+                    blocks: ranges,
+                    encrypted: false,
+                    block_size: COMPRESSION_BLOCK_SIZE
+                        .min(u32::try_from(data.len()).unwrap_or(u32::MAX)),
+                };
+
+                write_inline(&mut self.writer, &entry)?;
+                self.writer.write_all(&stored)?;
+                entry
+            }
+            _ => {
+                let entry = Entry {
+                    offset,
+                    size: data.len() as u64,
+                    uncompressed_size: data.len() as u64,
+                    compression: Compression::None,
+                    hash: super::sha1(data),
+                    blocks: vec![],
+                    encrypted: false,
+                    block_size: 0,
+                };
+
+                write_inline(&mut self.writer, &entry)?;
+                self.writer.write_all(data)?;
+                entry
+            }
+        };
+
+        verbose!(
+            "  Added: {name} ({} -> {} bytes)",
+            entry.uncompressed_size,
+            entry.size
+        );
+        self.names.insert(name.clone());
+        self.entries.push((name, entry));
+        Ok(())
+    }
+
+    /// Writes the index and footer
+    pub fn finish(mut self) -> Result<W, PakError> {
+        let index_offset = self.writer.stream_position()?;
+
+        let mut index = vec![];
+        super::write_string(&mut index, MOUNT_POINT)?;
+        index.write_all(&(self.entries.len() as i32).to_le_bytes())?;
+        for (name, entry) in &self.entries {
+            super::write_string(&mut index, name)?;
+            entry.write(&mut index)?;
+        }
+
+        self.writer.write_all(&index)?;
+
+        // Footer
+        self.writer.write_all(&[0])?; // Encrypted index
+        self.writer.write_all(&MAGIC.to_le_bytes())?;
+        self.writer.write_all(&VERSION.to_le_bytes())?;
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer.write_all(&(index.len() as u64).to_le_bytes())?;
+        self.writer.write_all(&super::sha1(&index))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Writes the entry header that precedes the file data. (Offset is always 0 here)
+fn write_inline<W: Write>(writer: &mut W, entry: &Entry) -> std::io::Result<()> {
+    let inline = Entry {
+        offset: 0,
+        ..entry.clone()
+    };
+    inline.write(writer)
+}
+
+/// Compresses the data in zlib blocks
+fn compress_blocks(data: &[u8]) -> std::io::Result<Vec<Vec<u8>>> {
+    let mut blocks = vec![];
+    for chunk in data.chunks(COMPRESSION_BLOCK_SIZE as usize) {
+        let mut encoder = ZlibEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(chunk)?;
+        blocks.push(encoder.finish()?);
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_footer() {
+        let mut writer = PakWriter::new(Cursor::new(vec![]), true);
+        writer
+            .add("CodeVein/Content/Test.uasset", &[7; 100_000])
+            .unwrap();
+        writer.add("CodeVein\\Content\\Test.uexp", b"data").unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let footer = &data[data.len() - 45..];
+        assert_eq!(footer[0], 0);
+        assert_eq!(footer[1..5], MAGIC.to_le_bytes());
+        assert_eq!(footer[5..9], VERSION.to_le_bytes());
+
+        let index_offset = u64::from_le_bytes(footer[9..17].try_into().unwrap()) as usize;
+        let index_size = u64::from_le_bytes(footer[17..25].try_into().unwrap()) as usize;
+        assert_eq!(index_offset + index_size, data.len() - 45);
+
+        let index = &data[index_offset..index_offset + index_size];
+        assert_eq!(footer[25..45], crate::pak::sha1(index));
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let mut writer = PakWriter::new(Cursor::new(vec![]), false);
+        assert!(writer.add("../Test.uasset", b"").is_err());
+        assert!(writer.add("/Test.uasset", b"").is_err());
+        writer.add("Test.uasset", b"").unwrap();
+        assert!(writer.add("Test.uasset", b"").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use glob::{MatchOptions, Pattern, PatternError};
+use std::path::Path;
+
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Include/exclude pattern for content paths.
+///
+/// Plain paths match the file or folder (and everything inside it) with whole
+/// path components. The last component also matches files by the name before
+/// the extension, so assets can be included without their extensions (`.uasset`,
+/// `.uexp`, `.ubulk`...). Paths containing glob characters are matched as globs
+/// against the file and each of its parent folders.
+#[derive(Debug)]
+pub enum PathPattern {
+    Path(Vec<String>),
+    Glob(Pattern),
+}
+
+impl PathPattern {
+    pub fn new<P: AsRef<Path>>(pattern: P) -> Result<Self, PatternError> {
+        let components = components(pattern.as_ref());
+
+        if components.iter().any(|c| c.contains(['*', '?', '['])) {
+            Ok(Self::Glob(Pattern::new(&components.join("/"))?))
+        } else {
+            let lowercase = components.iter().map(|c| c.to_lowercase()).collect();
+            Ok(Self::Path(lowercase))
+        }
+    }
+
+    /// Checks if the relative path matches the pattern
+    pub fn matches<P: AsRef<Path>>(&self, path: P) -> bool {
+        let components = components(path.as_ref());
+
+        match self {
+            Self::Path(pattern) => {
+                if pattern.len() > components.len() {
+                    return false;
+                }
+
+                // An empty pattern matches everything
+                let Some((last, parents)) = pattern.split_last() else {
+                    return true;
+                };
+                let component = components[parents.len()].to_lowercase();
+                let is_file = pattern.len() == components.len();
+
+                parents
+                    .iter()
+                    .zip(&components)
+                    .all(|(p, c)| *p == c.to_lowercase())
+                    && (*last == component
+                        || (is_file && component.split('.').next() == Some(last.as_str())))
+            }
+            Self::Glob(pattern) => (1..=components.len())
+                .rev()
+                .any(|len| pattern.matches_with(&components[..len].join("/"), OPTIONS)),
+        }
+    }
+}
+
+/// Splits the path into components regardless of the separator used
+fn components(path: &Path) -> Vec<String> {
+    path.to_string_lossy()
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_pattern() {
+        let pattern = PathPattern::new("ModResources").unwrap();
+        assert!(pattern.matches("ModResources/BP_Test.uasset"));
+        assert!(pattern.matches("modresources\\Sub\\BP_Test.uasset"));
+        assert!(!pattern.matches("ModResources2/BP_Test.uasset"));
+
+        let pattern = PathPattern::new("ModResources\\Stateful").unwrap();
+        assert!(pattern.matches("ModResources/Stateful/BP_Test.uexp"));
+        assert!(!pattern.matches("ModResources/BP_Test.uexp"));
+
+        // Assets without their extensions
+        let pattern =
+            PathPattern::new("Environment\\Base01_Hideout\\Blueprints\\BP_FieldTrigger_JukeBox")
+                .unwrap();
+        let dir = "Environment/Base01_Hideout/Blueprints";
+        assert!(pattern.matches(format!("{dir}/BP_FieldTrigger_JukeBox.uasset")));
+        assert!(pattern.matches(format!("{dir}/bp_fieldtrigger_jukebox.uexp")));
+        assert!(pattern.matches(format!("{dir}/BP_FieldTrigger_JukeBox.ubulk")));
+        assert!(!pattern.matches(format!("{dir}/BP_FieldTrigger_JukeBox2.uasset")));
+        assert!(!pattern.matches(format!("{dir}/BP_FieldTrigger.uasset")));
+
+        // Only files match by the name before the extension
+        let pattern = PathPattern::new("Maps/work").unwrap();
+        assert!(!pattern.matches("Maps/work.old/work.umap"));
+    }
+
+    #[test]
+    fn test_glob_pattern() {
+        let pattern = PathPattern::new("Maps/**/*_BuiltData.*").unwrap();
+        assert!(pattern.matches("Maps/work_BuiltData.uasset"));
+        assert!(pattern.matches("Maps/Sub/Level/work_BuiltData.uexp"));
+        assert!(!pattern.matches("Maps/work.umap"));
+
+        // Matching folders include their contents
+        let pattern = PathPattern::new("ModResources/BP_*").unwrap();
+        assert!(pattern.matches("ModResources/BP_Ladder.uasset"));
+        assert!(pattern.matches("ModResources/BP_Folder/Test.uasset"));
+        assert!(!pattern.matches("ModResources/Sub/BP_Ladder.uasset"));
+    }
+}
@@ -6,3 +6,8 @@ pub const REPLACE: &str = "PROJECTNAME";
 pub fn dir() -> std::path::PathBuf {
     crate::EXEDIR.join("resources")
 }
+
+/// File holding the version of the downloaded resources
+pub fn version_file() -> std::path::PathBuf {
+    dir().join("version")
+}
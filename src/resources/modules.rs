@@ -1,6 +1,9 @@
 use crate::resources::REPLACE;
+use crate::utils::native_path;
 use anyhow::{anyhow, Result};
 use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
@@ -9,9 +12,23 @@ const REL_PATH: &str = "modules";
 const CONFIG_FILE: &str = "module.toml";
 
 pub fn load() -> Result<Vec<Module>, std::io::Error> {
-    let module_dirs = std::fs::read_dir(dir())?;
     let mut modules = vec![];
 
+    for module_config_path in config_files()? {
+        match Module::load(module_config_path) {
+            Ok(module) => modules.push(module),
+            Err(err) => warning!("Failed to load module: {}", err),
+        }
+    }
+
+    Ok(modules)
+}
+
+/// Paths of the module configs (module.toml) in the modules directory
+pub fn config_files() -> Result<Vec<PathBuf>, std::io::Error> {
+    let module_dirs = std::fs::read_dir(dir())?;
+    let mut files = vec![];
+
     for entry in module_dirs.flatten() {
         let module_config_path = {
             let mut path = entry.path();
@@ -20,14 +37,111 @@ pub fn load() -> Result<Vec<Module>, std::io::Error> {
         };
 
         if module_config_path.is_file() {
-            match Module::load(module_config_path) {
-                Ok(module) => modules.push(module),
-                Err(err) => warning!("Failed to load module: {}", err),
+            files.push(module_config_path);
+        }
+    }
+
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Selects the modules by name, pulls in their dependencies transitively
+/// and sorts them so every module is installed after its dependencies.
+///
+/// The order of the selection is kept where possible. Pulled in dependencies
+/// are placed right before the first module requiring them.
+pub fn resolve(selected: &[String], available: &[Module]) -> Result<Vec<Module>> {
+    let find = |name: &str| available.iter().find(|m| m.name() == name);
+
+    // Collect the modules with the index of the first selected module requiring them
+    let mut priority: HashMap<&str, usize> = HashMap::new();
+    let mut order: Vec<&Module> = vec![];
+    for (index, name) in selected.iter().enumerate() {
+        let mut stack = vec![(name.as_str(), None::<&str>)];
+        while let Some((name, required_by)) = stack.pop() {
+            if priority.contains_key(name) {
+                continue;
+            }
+
+            let module = find(name).ok_or_else(|| match required_by {
+                Some(parent) => anyhow!("Module not found: {name} (required by {parent})"),
+                None => anyhow!("Module not found: {name}"),
+            })?;
+
+            priority.insert(module.name(), index);
+            order.push(module);
+            for dependency in module.dependencies() {
+                stack.push((dependency, Some(module.name())));
             }
         }
     }
 
-    Ok(modules)
+    // Count the modules that (transitively) depend on each module
+    let mut dependents: HashMap<&str, usize> = HashMap::new();
+    for module in &order {
+        let mut visited = BTreeSet::new();
+        let mut stack: Vec<&str> = module.dependencies().iter().map(String::as_str).collect();
+        while let Some(name) = stack.pop() {
+            if visited.insert(name) {
+                if let Some(dep) = find(name) {
+                    stack.extend(dep.dependencies().iter().map(String::as_str));
+                }
+            }
+        }
+        for name in visited {
+            *dependents.entry(name).or_default() += 1;
+        }
+    }
+
+    // Topological sort (Kahn's algorithm)
+    let mut remaining: HashMap<&str, usize> = order
+        .iter()
+        .map(|m| (m.name(), m.dependencies().len()))
+        .collect();
+    let mut ready: BTreeSet<(usize, Reverse<usize>, &str)> = BTreeSet::new();
+    let key = |name: &'_ str| {
+        let dependents = dependents.get(name).copied().unwrap_or_default();
+        (priority[name], Reverse(dependents))
+    };
+
+    for (name, count) in &remaining {
+        if *count == 0 {
+            let (p, d) = key(name);
+            ready.insert((p, d, name));
+        }
+    }
+
+    let mut out = Vec::with_capacity(order.len());
+    while let Some((_, _, name)) = ready.pop_first() {
+        remaining.remove(name);
+        let module = find(name).expect("Resolved module disappeared!");
+        out.push(module.clone());
+
+        for other in &order {
+            let deps = other.dependencies().iter().filter(|d| *d == name).count();
+            if deps == 0 {
+                continue;
+            }
+            if let Some(count) = remaining.get_mut(other.name()) {
+                *count -= deps;
+                if *count == 0 {
+                    let (p, d) = key(other.name());
+                    ready.insert((p, d, other.name()));
+                }
+            }
+        }
+    }
+
+    if !remaining.is_empty() {
+        let mut cycle: Vec<_> = remaining.into_keys().collect();
+        cycle.sort_unstable();
+        return Err(anyhow!(
+            "Dependency cycle between modules: {}",
+            cycle.join(", ")
+        ));
+    }
+
+    Ok(out)
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -43,6 +157,8 @@ pub struct Module {
     excludefiles: Vec<PathBuf>,
     #[serde(default)]
     pakinclude: Vec<PathBuf>,
+    #[serde(default)]
+    credits: Vec<String>,
 }
 
 impl Module {
@@ -62,6 +178,15 @@ impl Module {
         }
         module.path = path;
 
+        // Modules may be written on either platform
+        for file in module
+            .modifyfiles
+            .iter_mut()
+            .chain(&mut module.excludefiles)
+        {
+            *file = native_path(&*file);
+        }
+
         Ok(module)
     }
 
@@ -95,9 +220,15 @@ impl Module {
         &self.pakinclude
     }
 
-    pub fn install<P: AsRef<Path>>(&self, target: P, project_name: &str) -> Result<()> {
-        info!("Installing module: {}", self.name());
+    /// Gets the authors of the module
+    pub fn credits(&self) -> &Vec<String> {
+        &self.credits
+    }
+
+    /// Gets the files the module installs into a project
+    pub fn files(&self, project_name: &str) -> Result<Vec<ModuleFile>> {
         let cfgfile = Some(OsStr::new(CONFIG_FILE));
+        let mut files = vec![];
 
         for entry in WalkDir::new(self.path()).follow_links(true) {
             let entry = entry?;
@@ -118,10 +249,47 @@ impl Module {
             // Do Modify
             let modify = self.modifyfiles().contains(&rel_path);
 
+            let target = if modify {
+                renamefile(&rel_path, project_name)?
+            } else {
+                rel_path.clone()
+            };
+
+            files.push(ModuleFile {
+                source: abs_path.to_path_buf(),
+                relative: rel_path,
+                target,
+                modify,
+            });
+        }
+
+        Ok(files)
+    }
+
+    pub fn install<P: AsRef<Path>>(&self, target: P, project_name: &str) -> Result<()> {
+        info!("Installing module: {}", self.name());
+
+        for file in self.files(project_name)? {
+            let abs_path = &file.source;
+            let rel_path = &file.relative;
+            let modify = file.modify;
+
             // Install file
-            let mut target_path = target.as_ref().join(&rel_path);
-            if modify {
-                target_path = renamefile(target_path, project_name)?;
+            let target_path = target.as_ref().join(&file.target);
+
+            if crate::utils::dry_run() {
+                let action = match (target_path.is_file(), file.is_merged()) {
+                    (true, true) => "Merge",
+                    (true, false) => "Replace",
+                    (false, _) if file.modify => "Copy (with rename)",
+                    (false, _) => "Copy",
+                };
+                planned!(
+                    "{action} file: {} -> {}",
+                    rel_path.display(),
+                    target_path.display()
+                );
+                continue;
             }
 
             if target_path.is_file() {
@@ -177,6 +345,35 @@ impl Module {
     }
 }
 
+/// File installed by a module
+#[derive(Debug, Clone)]
+pub struct ModuleFile {
+    /// Absolute path of the file in the module
+    pub source: PathBuf,
+    /// Path relative to the module directory
+    pub relative: PathBuf,
+    /// Path relative to the project directory (renamed if needed)
+    pub target: PathBuf,
+    /// Whether the project name is replaced in the file
+    pub modify: bool,
+}
+
+impl ModuleFile {
+    /// Contents of the file as installed into the project
+    pub fn content(&self, project_name: &str) -> Result<Vec<u8>, std::io::Error> {
+        if self.modify {
+            Ok(modifyfile(&self.source, project_name)?.into_bytes())
+        } else {
+            std::fs::read(&self.source)
+        }
+    }
+
+    /// Whether the file is merged into the existing project file instead of replacing it
+    pub fn is_merged(&self) -> bool {
+        self.target.extension().and_then(OsStr::to_str) == Some("uproject")
+    }
+}
+
 fn renamefile<P: AsRef<Path>>(file: P, replace: &str) -> Result<PathBuf> {
     let filename = file
         .as_ref()
@@ -216,6 +413,52 @@ fn dir() -> PathBuf {
 mod tests {
     use super::*;
 
+    fn module(name: &str, dependencies: &[&str]) -> Module {
+        Module {
+            name: name.to_string(),
+            path: PathBuf::new(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            modifyfiles: vec![],
+            excludefiles: vec![],
+            pakinclude: vec![],
+            credits: vec![],
+        }
+    }
+
+    fn names(modules: &[Module]) -> Vec<&str> {
+        modules.iter().map(Module::name).collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let available = load().unwrap();
+
+        let resolved = resolve(&["stateful".to_string()], &available).unwrap();
+        assert_eq!(
+            names(&resolved),
+            ["base", "extensions", "enemies", "interactive", "stateful"]
+        );
+
+        // Already valid order is kept
+        let selected = ["base", "gitsupport", "extensions", "ladder"].map(String::from);
+        let resolved = resolve(&selected, &available).unwrap();
+        assert_eq!(names(&resolved), selected);
+    }
+
+    #[test]
+    fn test_resolve_cycle() {
+        let available = vec![
+            module("a", &["b"]),
+            module("b", &["c"]),
+            module("c", &["a"]),
+        ];
+        let err = resolve(&["a".to_string()], &available).unwrap_err();
+        assert!(err.to_string().contains("a, b, c"));
+
+        let err = resolve(&["d".to_string()], &available).unwrap_err();
+        assert!(err.to_string().contains("Module not found: d"));
+    }
+
     #[test]
     fn test_modules_load() {
         let module_count = std::fs::read_dir(dir())
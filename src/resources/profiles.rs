@@ -19,6 +19,20 @@ pub fn load() -> Result<Profiles> {
     Ok(profiles)
 }
 
+/// Loads the profiles merged with the user defined profiles of the tool config (if there is one)
+pub fn load_merged() -> Result<Profiles> {
+    let mut profiles = load()?;
+    if !crate::config::ToolConfig::check() {
+        return Ok(profiles);
+    }
+
+    verbose!("Loading tool config...");
+    let config = crate::config::ToolConfig::load()?;
+    profiles.extend(config.profiles().to_owned());
+
+    Ok(profiles)
+}
+
 fn file() -> PathBuf {
     let mut path = super::dir();
     path.push(REL_PATH);
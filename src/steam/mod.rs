@@ -0,0 +1,143 @@
+mod vdf;
+
+use std::path::{Path, PathBuf};
+
+/// Steam app id of CODE VEIN
+const APP_ID: &str = "678960";
+
+/// Environment variable overriding the Steam install folder
+const ROOT_ENV: &str = "CVMODTOOL_STEAM_DIR";
+
+/// Finds the CODE VEIN install folder in the Steam libraries
+pub fn find_game() -> Option<PathBuf> {
+    roots()
+        .iter()
+        .flat_map(|root| libraries(root))
+        .find_map(|library| find_app(&library, APP_ID))
+}
+
+/// The "~mods" folder of the game install
+pub fn moddir(game: &Path) -> PathBuf {
+    ["CodeVein", "Content", "Paks", "~mods"]
+        .iter()
+        .fold(game.to_path_buf(), |path, c| path.join(c))
+}
+
+/// Possible Steam install folders
+fn roots() -> Vec<PathBuf> {
+    if let Some(root) = std::env::var_os(ROOT_ENV) {
+        return vec![PathBuf::from(root)];
+    }
+
+    let mut roots = vec![];
+
+    #[cfg(windows)]
+    for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+        if let Some(dir) = std::env::var_os(var) {
+            roots.push(PathBuf::from(dir).join("Steam"));
+        }
+    }
+
+    #[cfg(not(windows))]
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        roots.push(home.join(".steam/steam"));
+        roots.push(home.join(".local/share/Steam"));
+        roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+    }
+
+    roots.retain(|r| r.is_dir());
+    roots
+}
+
+/// Library folders of the Steam install (including itself)
+fn libraries(root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![root.to_path_buf()];
+
+    for file in ["steamapps/libraryfolders.vdf", "config/libraryfolders.vdf"] {
+        if let Ok(text) = std::fs::read_to_string(root.join(file)) {
+            libraries.extend(parse_libraries(&text));
+            break;
+        }
+    }
+
+    // The same library can be found through symlinks (e.g. ~/.steam/steam)
+    let mut unique: Vec<PathBuf> = vec![];
+    for library in libraries {
+        let canonical = library.canonicalize().unwrap_or(library);
+        if !unique.contains(&canonical) {
+            unique.push(canonical);
+        }
+    }
+    unique
+}
+
+/// Install folder of the app in the library
+fn find_app(library: &Path, app_id: &str) -> Option<PathBuf> {
+    let steamapps = library.join("steamapps");
+    let manifest = steamapps.join(format!("appmanifest_{app_id}.acf"));
+    let text = std::fs::read_to_string(manifest).ok()?;
+    let (id, installdir) = parse_manifest(&text)?;
+
+    let install = steamapps.join("common").join(installdir);
+    (id == app_id && install.is_dir()).then_some(install)
+}
+
+/// Library paths from libraryfolders.vdf
+fn parse_libraries(text: &str) -> Vec<PathBuf> {
+    let Ok(root) = vdf::parse(text) else {
+        return vec![];
+    };
+    let Some(folders) = root.get("libraryfolders") else {
+        return vec![];
+    };
+
+    folders
+        .entries()
+        .iter()
+        .filter(|(key, _)| key.parse::<u32>().is_ok())
+        .filter_map(|(_, value)| match value {
+            // Legacy format: "1" "D:\\SteamLibrary"
+            vdf::Value::String(path) => Some(path.as_str()),
+            vdf::Value::Object(_) => value.get("path").and_then(|p| p.as_str()),
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// App id and install folder name from appmanifest_*.acf
+fn parse_manifest(text: &str) -> Option<(String, String)> {
+    let root = vdf::parse(text).ok()?;
+    let state = root.get("AppState")?;
+    let id = state.get("appid")?.as_str()?;
+    let installdir = state.get("installdir")?.as_str()?;
+    Some((id.to_string(), installdir.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libraries() {
+        let libraries = parse_libraries(include_str!("samples/libraryfolders.vdf"));
+        assert_eq!(
+            libraries,
+            [
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from(r"D:\SteamLibrary")
+            ]
+        );
+
+        let libraries = parse_libraries(include_str!("samples/libraryfolders_legacy.vdf"));
+        assert_eq!(libraries, [PathBuf::from("/mnt/games/SteamLibrary")]);
+    }
+
+    #[test]
+    fn test_manifest() {
+        let manifest = parse_manifest(include_str!("samples/appmanifest_678960.acf"));
+        assert_eq!(
+            manifest,
+            Some((APP_ID.to_string(), "CODE VEIN".to_string()))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::iter::Peekable;
+use std::str::Chars;
+use thiserror::Error;
+
+/// Value of the Valve KeyValues (VDF) text format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    /// Keys are kept in order and may repeat
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// First value with the key (case-insensitive like Steam)
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Self::String(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            Self::Object(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, Value)] {
+        match self {
+            Self::Object(entries) => entries,
+            Self::String(_) => &[],
+        }
+    }
+}
+
+/// Parses the text into an object holding the root keys
+pub fn parse(text: &str) -> Result<Value, VdfError> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+
+    let entries = parser.object(false)?;
+    Ok(Value::Object(entries))
+}
+
+enum Token {
+    String(String),
+    Open,
+    Close,
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn object(&mut self, nested: bool) -> Result<Vec<(String, Value)>, VdfError> {
+        let mut entries = vec![];
+
+        loop {
+            let key = match self.token()? {
+                Some(Token::String(key)) => key,
+                Some(Token::Close) if nested => return Ok(entries),
+                None if !nested => return Ok(entries),
+                None => return Err(VdfError::UnexpectedEnd),
+                Some(_) => return Err(VdfError::Unexpected(self.line)),
+            };
+
+            let value = match self.token()? {
+                Some(Token::String(value)) => Value::String(value),
+                Some(Token::Open) => Value::Object(self.object(true)?),
+                Some(Token::Close) => return Err(VdfError::Unexpected(self.line)),
+                None => return Err(VdfError::UnexpectedEnd),
+            };
+
+            // Skip conditionals (e.g. [$WIN32])
+            self.skip_whitespace();
+            if self.chars.peek() == Some(&'[') {
+                while self.chars.next_if(|&c| c != ']' && c != '\n').is_some() {}
+                if self.chars.next() != Some(']') {
+                    return Err(VdfError::Unexpected(self.line));
+                }
+            }
+
+            entries.push((key, value));
+        }
+    }
+
+    fn token(&mut self) -> Result<Option<Token>, VdfError> {
+        self.skip_whitespace();
+
+        match self.chars.next() {
+            None => Ok(None),
+            Some('{') => Ok(Some(Token::Open)),
+            Some('}') => Ok(Some(Token::Close)),
+            Some('"') => self.quoted().map(|s| Some(Token::String(s))),
+            Some(c) => {
+                let mut value = String::from(c);
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | '"'))
+                {
+                    value.push(c);
+                }
+                Ok(Some(Token::String(value)))
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, VdfError> {
+        let mut value = String::new();
+
+        loop {
+            match self.chars.next() {
+                None => return Err(VdfError::UnexpectedEnd),
+                Some('"') => return Ok(value),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('\\' | '"')) => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => return Err(VdfError::UnexpectedEnd),
+                },
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    /// Skips whitespace and // comments
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.chars.peek().copied() {
+                Some('\n') => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('/') if self.chars.clone().nth(1) == Some('/') => {
+                    while self.chars.next_if(|&c| c != '\n').is_some() {}
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum VdfError {
+    #[error("Unexpected end of file")]
+    UnexpectedEnd,
+    #[error("Unexpected token at line {0}")]
+    Unexpected(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"
+            // Comment
+            "Root"
+            {
+                "Key"       "Value"
+                "Escaped"   "C:\\Program Files (x86)\\Steam \"x\""
+                Unquoted    1234
+                Path        /home/user/.steam // Comment
+                "Nested"    { "Inner" "" }
+                "Cond"      "A" [$WIN32]
+            }
+        "#;
+
+        let root = parse(text).unwrap();
+        let object = root.get("root").unwrap();
+        assert_eq!(object.get("key").unwrap().as_str(), Some("Value"));
+        assert_eq!(
+            object.get("Escaped").unwrap().as_str(),
+            Some(r#"C:\Program Files (x86)\Steam "x""#)
+        );
+        assert_eq!(object.get("Unquoted").unwrap().as_str(), Some("1234"));
+        assert_eq!(
+            object.get("Path").unwrap().as_str(),
+            Some("/home/user/.steam")
+        );
+        assert_eq!(
+            object.get("Nested").unwrap().get("Inner").unwrap().as_str(),
+            Some("")
+        );
+        assert_eq!(object.get("Cond").unwrap().as_str(), Some("A"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse(r#""Root" { "Key" "Value""#),
+            Err(VdfError::UnexpectedEnd)
+        ));
+        assert!(matches!(parse("\"Root\" }"), Err(VdfError::Unexpected(1))));
+        assert!(matches!(
+            parse("\"Key\" \"Value"),
+            Err(VdfError::UnexpectedEnd)
+        ));
+    }
+}
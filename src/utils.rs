@@ -1,5 +1,7 @@
-use once_cell::sync::Lazy;
-use std::path::PathBuf;
+use once_cell::sync::{Lazy, OnceCell};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
+
+pub static DRY_RUN: OnceCell<bool> = OnceCell::new();
 
 pub static EXE: Lazy<PathBuf> =
     Lazy::new(|| std::env::current_exe().expect("Failed to get self executable path!"));
@@ -14,6 +16,27 @@ pub static EXEDIR: Lazy<PathBuf> = Lazy::new(|| {
 pub static WORKDIR: Lazy<PathBuf> =
     Lazy::new(|| std::env::current_dir().expect("Failed to get working directory path!"));
 
+/// Only print the planned file operations without touching the disk
+pub fn dry_run() -> bool {
+    DRY_RUN.get().copied().unwrap_or_default()
+}
+
+/// Converts a path written with either separator to the native one
+pub fn native_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref().to_string_lossy();
+    PathBuf::from(path.replace(['/', '\\'], MAIN_SEPARATOR_STR))
+}
+
+/// Separator independent key of a relative path
+pub fn path_key<P: AsRef<Path>>(path: P) -> String {
+    path.as_ref().to_string_lossy().replace('\\', "/")
+}
+
+/// SHA1 hex of the content
+pub fn content_hash(content: &[u8]) -> String {
+    crate::pak::hash_hex(&crate::pak::sha1(content))
+}
+
 /// Json merge both map and array
 pub fn json_merge(a: &mut json::Value, b: json::Value) {
     match (a, b) {
@@ -30,3 +53,43 @@ pub fn json_merge(a: &mut json::Value, b: json::Value) {
         (a, b) => *a = b,
     }
 }
+
+/// Formats the time as `YYYY-MM-DD HH:MM` (UTC)
+pub fn format_time(time: std::time::SystemTime) -> String {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    // Civil from days algorithm (Howard Hinnant)
+    let days = (secs / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let hour = secs % 86400 / 3600;
+    let minute = secs % 3600 / 60;
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}")
+}
+
+/// Formats the byte count in a human readable form
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.2} {}", UNITS[unit])
+    }
+}